@@ -0,0 +1,294 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use naga_to_tokenstream::{
+    MinifyLevel, ModuleSource, ModuleToTokens, ModuleToTokensConfig, SpirvOptions,
+    ValidationOptions,
+};
+
+/// Generates Rust definitions for the types, constants, overrides, globals and entry points of a shader.
+#[derive(Parser)]
+#[command(name = "naga-to-rust", version)]
+struct Args {
+    /// The shader to generate definitions for. SPIR-V (`.spv`), GLSL (`.vert`, `.frag`, `.comp` or any other
+    /// extension with `--stage`) and WGSL (anything else) are supported.
+    input: PathBuf,
+    /// The file to write the generated definitions to. Definitions are written to stdout if this isn't given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Instead of writing the output file, exit with an error if it doesn't match the definitions that would be
+    /// generated.
+    #[arg(long, requires = "output")]
+    check: bool,
+    /// The stage of a GLSL shader, if it can't be told from the file extension. Giving a stage causes the shader to
+    /// be parsed as GLSL.
+    #[arg(long, value_enum)]
+    stage: Option<Stage>,
+    /// A preprocessor definition for a GLSL shader, as `NAME=VALUE` or just `NAME`. Can be given multiple times.
+    #[arg(long = "define", short = 'D', value_name = "NAME=VALUE")]
+    defines: Vec<String>,
+    /// Generate `glam` types.
+    #[arg(long)]
+    glam: bool,
+    /// Generate `encase` types.
+    #[arg(long)]
+    encase: bool,
+    /// Generate `naga` types.
+    #[arg(long)]
+    naga: bool,
+    /// Generate `wgpu` helpers.
+    #[arg(long)]
+    wgpu: bool,
+    /// Generate `Default` implementations and `zeroed` constructors for structs.
+    #[arg(long)]
+    default: bool,
+    /// Derive `serde::Serialize` and `serde::Deserialize` on structs.
+    #[arg(long)]
+    serde: bool,
+    /// Embed the module and each entry point compiled to SPIR-V.
+    #[arg(long)]
+    spirv: bool,
+    /// The version of SPIR-V to target with `--spirv`, such as `1.3`.
+    #[arg(long, value_name = "MAJOR.MINOR", value_parser = parse_spirv_version, default_value = "1.0")]
+    spirv_version: (u8, u8),
+    /// Include debug information, including the shader's source code, in the SPIR-V embedded with `--spirv`.
+    #[arg(long)]
+    spirv_debug: bool,
+    /// Embed each entry point translated to GLSL.
+    #[arg(long)]
+    glsl: bool,
+    /// Embed the module and each entry point translated to HLSL.
+    #[arg(long)]
+    hlsl: bool,
+    /// Embed the module and each entry point translated to the Metal Shading Language.
+    #[arg(long)]
+    msl: bool,
+    /// Only generate the struct with this name. Can be given multiple times.
+    #[arg(long = "struct", value_name = "NAME")]
+    structs: Vec<String>,
+    /// An extra derive macro to apply to every struct, such as `Copy`. Can be given multiple times.
+    #[arg(long = "derive", value_name = "PATH")]
+    derives: Vec<String>,
+    /// Embed the shader's source code verbatim as the `SOURCE` constant, rather than source written out from the
+    /// parsed shader. Only applies to WGSL shaders.
+    #[arg(long)]
+    embed_source: bool,
+    /// How much to minify the WGSL written out as `SOURCE` and `EXCLUSIVE_SOURCE`.
+    #[arg(long, value_enum, default_value = "none")]
+    minify: Minify,
+    /// A capability of the target which the shader may use, such as `PUSH_CONSTANT`, as named by
+    /// `naga::valid::Capabilities`. Can be given multiple times. Every capability is allowed if none are given.
+    #[arg(long = "capability", value_name = "NAME", value_parser = parse_capability)]
+    capabilities: Vec<naga::valid::Capabilities>,
+    /// A validation check to perform, such as `BINDINGS`, as named by `naga::valid::ValidationFlags`. Can be given
+    /// multiple times. Every check is performed if none are given.
+    #[arg(long = "validate", value_name = "NAME", value_parser = parse_validation_flag)]
+    validation_flags: Vec<naga::valid::ValidationFlags>,
+}
+
+/// The stage of a GLSL shader.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Stage {
+    Vertex,
+    Fragment,
+    Compute,
+}
+
+/// How much to minify WGSL sources.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Minify {
+    None,
+    Whitespace,
+    Full,
+}
+
+impl From<Minify> for MinifyLevel {
+    fn from(minify: Minify) -> Self {
+        match minify {
+            Minify::None => MinifyLevel::None,
+            Minify::Whitespace => MinifyLevel::Whitespace,
+            Minify::Full => MinifyLevel::Full,
+        }
+    }
+}
+
+/// Parses the name of a capability, such as `PUSH_CONSTANT` or `push-constant`.
+fn parse_capability(name: &str) -> Result<naga::valid::Capabilities, String> {
+    naga::valid::Capabilities::from_name(&name.to_uppercase().replace('-', "_"))
+        .ok_or_else(|| format!("`{}` isn't a capability known to naga", name))
+}
+
+/// Parses the name of a validation check, such as `BINDINGS` or `bindings`.
+fn parse_validation_flag(name: &str) -> Result<naga::valid::ValidationFlags, String> {
+    naga::valid::ValidationFlags::from_name(&name.to_uppercase().replace('-', "_"))
+        .ok_or_else(|| format!("`{}` isn't a validation check known to naga", name))
+}
+
+/// Parses a SPIR-V version given as `MAJOR.MINOR`.
+fn parse_spirv_version(version: &str) -> Result<(u8, u8), String> {
+    version
+        .split_once('.')
+        .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)))
+        .ok_or_else(|| format!("`{}` isn't a version of the form `MAJOR.MINOR`", version))
+}
+
+impl From<Stage> for naga::ShaderStage {
+    fn from(stage: Stage) -> Self {
+        match stage {
+            Stage::Vertex => naga::ShaderStage::Vertex,
+            Stage::Fragment => naga::ShaderStage::Fragment,
+            Stage::Compute => naga::ShaderStage::Compute,
+        }
+    }
+}
+
+/// Reads and parses the shader at the given path, giving the module along with its source code if it was text. Errors
+/// are given ready to be printed.
+fn parse(args: &Args) -> Result<(naga::Module, Option<ModuleSource>), String> {
+    let path = &args.input;
+    let read_error = |error| format!("error: failed to read `{}`: {}", path.display(), error);
+
+    if naga_to_tokenstream::front::is_spirv_path(path) {
+        let data = std::fs::read(path).map_err(read_error)?;
+        let module = naga_to_tokenstream::front::parse_spirv(&data)
+            .map_err(|error| format!("error: failed to parse `{}`: {}", path.display(), error))?;
+        return Ok((module, None));
+    }
+
+    let code = std::fs::read_to_string(path).map_err(read_error)?;
+    let is_glsl = path
+        .extension()
+        .is_some_and(|extension| extension == "glsl");
+    let stage = args
+        .stage
+        .map(naga::ShaderStage::from)
+        .or_else(|| naga_to_tokenstream::front::glsl_stage_from_path(path));
+    let source = |code, embed_verbatim| ModuleSource {
+        code,
+        path: Some(path.clone()),
+        embed_verbatim,
+    };
+    match stage {
+        Some(stage) => {
+            let defines = args.defines.iter().map(|define| match define.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => (define.clone(), String::new()),
+            });
+            let module = naga_to_tokenstream::front::parse_glsl(&code, stage, defines)
+                .map_err(|error| error.emit_to_string(&code))?;
+            // The `SOURCE` constant is always WGSL, so GLSL can't be embedded verbatim
+            Ok((module, Some(source(code, false))))
+        }
+        None if is_glsl => Err(format!(
+            "error: the stage of `{}` can't be told from its extension, so must be given with `--stage`",
+            path.display()
+        )),
+        None => {
+            let module = naga::front::wgsl::parse_str(&code)
+                .map_err(|error| error.emit_to_string_with_path(&code, path))?;
+            Ok((module, Some(source(code, args.embed_source))))
+        }
+    }
+}
+
+/// Parses and converts the input shader, validating it along the way, giving the pretty-printed Rust definitions.
+/// Errors are given ready to be printed.
+fn generate(args: &Args) -> Result<String, String> {
+    let (module, source) = parse(args)?;
+
+    let struct_derives = args
+        .derives
+        .iter()
+        .map(|derive| {
+            syn::parse_str(derive)
+                .map_err(|error| format!("error: invalid derive `{}`: {}", derive, error))
+        })
+        .collect::<Result<_, _>>()?;
+    let cfg = ModuleToTokensConfig {
+        structs_filter: (!args.structs.is_empty()).then(|| args.structs.iter().cloned().collect()),
+        gen_glam: args.glam,
+        gen_encase: args.encase,
+        gen_naga: args.naga,
+        gen_wgpu: args.wgpu,
+        gen_default: args.default,
+        gen_serde: args.serde,
+        gen_spirv: args.spirv,
+        gen_glsl: args.glsl,
+        gen_hlsl: args.hlsl,
+        gen_msl: args.msl,
+        spirv_options: SpirvOptions {
+            lang_version: args.spirv_version,
+            debug_info: args.spirv_debug,
+            ..Default::default()
+        },
+        struct_derives,
+        source,
+        validation: ValidationOptions {
+            flags: match args.validation_flags.is_empty() {
+                true => naga::valid::ValidationFlags::all(),
+                false => args.validation_flags.iter().copied().collect(),
+            },
+            capabilities: match args.capabilities.is_empty() {
+                true => naga::valid::Capabilities::all(),
+                false => args.capabilities.iter().copied().collect(),
+            },
+        },
+        minify: args.minify.into(),
+        ..Default::default()
+    };
+
+    // The module is validated while converting it, with any errors reported as diagnostics
+    let (items, diagnostics) = module.to_items_with_diagnostics(cfg);
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    if diagnostics.has_errors() {
+        return Err(format!(
+            "error: failed to convert `{}`",
+            args.input.display()
+        ));
+    }
+
+    Ok(prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: vec![],
+        items,
+    }))
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let generated = match generate(&args) {
+        Ok(generated) => generated,
+        Err(error) => {
+            eprintln!("{}", error.trim_end());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(output) = &args.output else {
+        print!("{}", generated);
+        return ExitCode::SUCCESS;
+    };
+
+    if args.check {
+        let existing = std::fs::read_to_string(output).unwrap_or_default();
+        if existing != generated {
+            eprintln!(
+                "error: `{}` is out of date with `{}`, regenerate it by running without `--check`",
+                output.display(),
+                args.input.display()
+            );
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if let Err(error) = std::fs::write(output, generated) {
+        eprintln!("error: failed to write `{}`: {}", output.display(), error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
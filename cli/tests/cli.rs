@@ -0,0 +1,197 @@
+use std::process::Command;
+
+const SHADER: &str = r#"
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+}
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+
+@compute
+@workgroup_size(64)
+fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+    particles[id.x].position += particles[id.x].velocity;
+}
+"#;
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("naga-to-rust-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn writes_to_stdout() {
+    let dir = temp_dir("stdout");
+    let input = dir.join("particles.wgsl");
+    std::fs::write(&input, SHADER).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_naga-to-rust"))
+        .arg(&input)
+        .args(["--glam", "--derive", "Copy"])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("#[derive(Debug, PartialEq, Clone, Copy)]"));
+    assert!(stdout.contains("pub position: glam::f32::Vec2"));
+    assert!(stdout.contains("pub const WORKGROUP_SIZE: [u32; 3] = [64u32, 1u32, 1u32];"));
+}
+
+#[test]
+fn checks_output_is_up_to_date() {
+    let dir = temp_dir("check");
+    let input = dir.join("particles.wgsl");
+    let output = dir.join("particles.rs");
+    std::fs::write(&input, SHADER).unwrap();
+
+    let run = |check: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_naga-to-rust"));
+        command.arg(&input).arg("-o").arg(&output);
+        if check {
+            command.arg("--check");
+        }
+        command.status().unwrap().success()
+    };
+
+    // Missing output is stale
+    assert!(!run(true));
+    assert!(run(false));
+    assert!(run(true));
+
+    // Changing the shader makes the output stale
+    std::fs::write(&input, SHADER.replace("64", "32")).unwrap();
+    assert!(!run(true));
+    assert!(run(false));
+    assert!(run(true));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reports_parse_errors() {
+    let dir = temp_dir("errors");
+    let input = dir.join("broken.wgsl");
+    std::fs::write(&input, "fn main( {}").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_naga-to-rust"))
+        .arg(&input)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("expected identifier"));
+}
+
+#[test]
+fn parses_glsl_with_defines() {
+    let dir = temp_dir("glsl");
+    let input = dir.join("blur.comp");
+    std::fs::write(
+        &input,
+        r#"#version 450
+layout(local_size_x = GROUP_SIZE) in;
+
+struct Pixel {
+    vec4 color;
+    float weight;
+};
+
+layout(set = 0, binding = 0) buffer Pixels {
+    Pixel pixels[];
+};
+
+void main() {
+    pixels[gl_GlobalInvocationID.x].weight *= 0.5;
+}
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_naga-to-rust"))
+        .arg(&input)
+        .args(["-D", "GROUP_SIZE=32"])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("pub struct Pixel"));
+    assert!(stdout.contains("pub const WORKGROUP_SIZE: [u32; 3] = [32u32, 1u32, 1u32];"));
+}
+
+#[test]
+fn validates_against_given_capabilities() {
+    let dir = temp_dir("capabilities");
+    let input = dir.join("push_constants.wgsl");
+    std::fs::write(
+        &input,
+        r#"
+var<push_constant> scale: f32;
+
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4<f32>(scale);
+}
+"#,
+    )
+    .unwrap();
+
+    let run = |capabilities: &[&str]| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_naga-to-rust"));
+        command.arg(&input);
+        for capability in capabilities {
+            command.args(["--capability", capability]);
+        }
+        command.output().unwrap()
+    };
+
+    // Every capability is allowed by default
+    assert!(run(&[]).status.success());
+    assert!(run(&["push-constant"]).status.success());
+
+    let output = run(&["FLOAT64"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("failed to convert"), "{}", stderr);
+
+    let output = run(&["not-a-capability"]);
+    assert!(!output.status.success());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn minifies_source() {
+    let dir = temp_dir("minify");
+    let input = dir.join("particles.wgsl");
+    std::fs::write(&input, SHADER).unwrap();
+
+    let run = |minify: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_naga-to-rust"))
+            .arg(&input)
+            .args(["--minify", minify])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let none = run("none");
+    let full = run("full");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(none.contains("pub const SOURCE: &'static str"));
+    assert!(full.contains("pub const SOURCE: &'static str"));
+    assert!(full.len() < none.len());
+}
@@ -0,0 +1,303 @@
+use crate::{diagnostics::Diagnostics, ModuleToTokensConfig};
+
+/// Describes the module being written, for use in diagnostics.
+#[cfg_attr(
+    not(any(
+        feature = "spv-out",
+        feature = "glsl-out",
+        feature = "hlsl-out",
+        feature = "msl-out"
+    )),
+    allow(dead_code)
+)]
+fn describe(entry_point: Option<&naga::EntryPoint>) -> String {
+    match entry_point {
+        Some(entry_point) => format!("the entry point `{}`", entry_point.name),
+        None => String::from("the module"),
+    }
+}
+
+/// Records that a backend source couldn't be generated because this crate was built without the matching feature.
+#[cfg_attr(
+    all(
+        feature = "spv-out",
+        feature = "glsl-out",
+        feature = "hlsl-out",
+        feature = "msl-out"
+    ),
+    allow(dead_code)
+)]
+fn missing_feature(constant: &str, feature: &str, diagnostics: &mut Diagnostics) {
+    diagnostics.warn(format!(
+        "the `{}` feature of `naga-to-tokenstream` isn't enabled, so no `{}` was generated",
+        feature, constant
+    ));
+}
+
+/// Gives the options for the SPIR-V writer described by the configuration.
+#[cfg(feature = "spv-out")]
+fn spirv_options(args: &ModuleToTokensConfig) -> naga::back::spv::Options<'_> {
+    let mut options = naga::back::spv::Options {
+        lang_version: args.spirv_options.lang_version,
+        bounds_check_policies: args.spirv_options.bounds_check_policies,
+        ..Default::default()
+    };
+
+    // The default depends on whether this crate was built with debug assertions, which shouldn't change the output
+    options.flags.set(
+        naga::back::spv::WriterFlags::DEBUG,
+        args.spirv_options.debug_info,
+    );
+    if let (true, Some(source)) = (args.spirv_options.debug_info, &args.source) {
+        options.debug_info = Some(naga::back::spv::DebugInfo {
+            source_code: &source.code,
+            file_name: source.path.as_deref().unwrap_or(std::path::Path::new("")),
+            language: if source.is_wgsl() {
+                naga::back::spv::SourceLanguage::WGSL
+            } else {
+                naga::back::spv::SourceLanguage::Unknown
+            },
+        });
+    }
+
+    options
+}
+
+/// Replaces the overrides of a module with their default values, since backends other than WGSL have no equivalent.
+/// If this fails, a warning is recorded explaining why the `generated` sources of the module being written won't be
+/// generated.
+#[cfg(any(
+    feature = "spv-out",
+    feature = "glsl-out",
+    feature = "hlsl-out",
+    feature = "msl-out"
+))]
+fn process_overrides<'a>(
+    module: &'a naga::Module,
+    info: &'a naga::valid::ModuleInfo,
+    entry_point: Option<&naga::EntryPoint>,
+    generated: &str,
+    diagnostics: &mut Diagnostics,
+) -> Option<(
+    std::borrow::Cow<'a, naga::Module>,
+    std::borrow::Cow<'a, naga::valid::ModuleInfo>,
+)> {
+    match naga::back::pipeline_constants::process_overrides(module, info, &Default::default()) {
+        Ok(processed) => Some(processed),
+        Err(error) => {
+            diagnostics.warn(format!(
+                "the overrides of {} couldn't be given their default values, so no {} generated: {}",
+                describe(entry_point),
+                generated,
+                error
+            ));
+            None
+        }
+    }
+}
+
+/// Converts a validated module into the shader languages requested in the configuration other than WGSL, giving
+/// constants holding the translated sources. If an entry point is given, SPIR-V and GLSL are written for only that
+/// entry point by giving pipeline options to their writers, while HLSL and MSL, which have no such options, are
+/// written from `retained`, the module retaining only that entry point and what it reaches, along with its info. GLSL
+/// can only be written for a single entry point, so is only generated in that case.
+///
+/// Overrides are replaced by their default values, since backends other than WGSL have no equivalent.
+#[cfg_attr(
+    not(any(
+        feature = "spv-out",
+        feature = "glsl-out",
+        feature = "hlsl-out",
+        feature = "msl-out"
+    )),
+    allow(unused_mut, unused_variables)
+)]
+pub(crate) fn make_backend_sources(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    entry_point: Option<&naga::EntryPoint>,
+    retained: Option<(&naga::Module, &naga::valid::ModuleInfo)>,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    let gen_glsl = args.gen_glsl && entry_point.is_some();
+    if !(args.gen_spirv || gen_glsl || args.gen_hlsl || args.gen_msl) {
+        return items;
+    }
+
+    #[cfg(not(feature = "spv-out"))]
+    if args.gen_spirv {
+        missing_feature("SPIRV", "spv-out", diagnostics);
+    }
+    #[cfg(not(feature = "glsl-out"))]
+    if gen_glsl {
+        missing_feature("GLSL", "glsl-out", diagnostics);
+    }
+    #[cfg(not(feature = "hlsl-out"))]
+    if args.gen_hlsl {
+        missing_feature("HLSL", "hlsl-out", diagnostics);
+    }
+    #[cfg(not(feature = "msl-out"))]
+    if args.gen_msl {
+        missing_feature("MSL", "msl-out", diagnostics);
+    }
+
+    #[cfg(any(
+        feature = "spv-out",
+        feature = "glsl-out",
+        feature = "hlsl-out",
+        feature = "msl-out"
+    ))]
+    {
+        // Without an entry point every backend writes the whole module, so it only needs processing once
+        let gen_retained = args.gen_hlsl || args.gen_msl;
+        let gen_whole = args.gen_spirv || gen_glsl || (entry_point.is_none() && gen_retained);
+        let whole = match gen_whole {
+            true => process_overrides(
+                module,
+                info,
+                entry_point,
+                match entry_point {
+                    Some(_) => "SPIR-V or GLSL sources were",
+                    None => "sources in other shader languages were",
+                },
+                diagnostics,
+            ),
+            false => None,
+        };
+        let processed_retained = match (entry_point, retained) {
+            (Some(_), Some((module, info))) if gen_retained => process_overrides(
+                module,
+                info,
+                entry_point,
+                "HLSL or MSL sources were",
+                diagnostics,
+            ),
+            _ => None,
+        };
+        #[cfg_attr(
+            not(any(feature = "hlsl-out", feature = "msl-out")),
+            allow(unused_variables)
+        )]
+        let retained = match entry_point {
+            Some(_) => processed_retained.as_ref(),
+            None => whole.as_ref(),
+        };
+
+        #[cfg(feature = "spv-out")]
+        if let (true, Some((module, info))) = (args.gen_spirv, &whole) {
+            let pipeline_options =
+                entry_point.map(|entry_point| naga::back::spv::PipelineOptions {
+                    shader_stage: entry_point.stage,
+                    entry_point: entry_point.name.clone(),
+                });
+            match naga::back::spv::write_vec(
+                module,
+                info,
+                &spirv_options(args),
+                pipeline_options.as_ref(),
+            ) {
+                Ok(words) => {
+                    items.push(syn::parse_quote! {
+                        #[doc = "The shader compiled to SPIR-V, with overrides set to their default values."]
+                        pub const SPIRV: &'static [u32] = &[#(#words),*];
+                    });
+                    if args.gen_wgpu {
+                        let label = match entry_point {
+                            Some(_) => quote::quote!(Some(NAME)),
+                            None => quote::quote!(None),
+                        };
+                        items.push(syn::parse_quote! {
+                            #[doc = "Gives a descriptor for creating a shader module from `SPIRV`, avoiding parsing WGSL at runtime. Requires the `spirv` feature of `wgpu`."]
+                            pub fn spirv_shader_module_descriptor() -> wgpu::ShaderModuleDescriptor<'static> {
+                                wgpu::ShaderModuleDescriptor {
+                                    label: #label,
+                                    source: wgpu::ShaderSource::SpirV(std::borrow::Cow::Borrowed(SPIRV)),
+                                }
+                            }
+                        });
+                    }
+                }
+                Err(error) => diagnostics.warn(format!(
+                    "failed to write {} as SPIR-V, so no `SPIRV` was generated: {}",
+                    describe(entry_point),
+                    error
+                )),
+            }
+        }
+
+        #[cfg(feature = "glsl-out")]
+        if let (true, Some(entry_point), Some((module, info))) = (gen_glsl, entry_point, &whole) {
+            let pipeline_options = naga::back::glsl::PipelineOptions {
+                shader_stage: entry_point.stage,
+                entry_point: entry_point.name.clone(),
+                multiview: None,
+            };
+            let options = naga::back::glsl::Options::default();
+            let mut source = String::new();
+            let written = naga::back::glsl::Writer::new(
+                &mut source,
+                module,
+                info,
+                &options,
+                &pipeline_options,
+                naga::proc::BoundsCheckPolicies::default(),
+            )
+            .and_then(|mut writer| writer.write());
+            match written {
+                Ok(_) => items.push(syn::parse_quote! {
+                    #[doc = "The shader translated to GLSL, with overrides set to their default values."]
+                    pub const GLSL: &'static str = #source;
+                }),
+                Err(error) => diagnostics.warn(format!(
+                    "failed to write {} as GLSL, so no `GLSL` was generated: {}",
+                    describe(Some(entry_point)),
+                    error
+                )),
+            }
+        }
+
+        #[cfg(feature = "hlsl-out")]
+        if let (true, Some((module, info))) = (args.gen_hlsl, retained) {
+            let options = naga::back::hlsl::Options::default();
+            let mut source = String::new();
+            let written =
+                naga::back::hlsl::Writer::new(&mut source, &options).write(module, info, None);
+            match written {
+                Ok(_) => items.push(syn::parse_quote! {
+                    #[doc = "The shader translated to HLSL, with overrides set to their default values."]
+                    pub const HLSL: &'static str = #source;
+                }),
+                Err(error) => diagnostics.warn(format!(
+                    "failed to write {} as HLSL, so no `HLSL` was generated: {}",
+                    describe(entry_point),
+                    error
+                )),
+            }
+        }
+
+        #[cfg(feature = "msl-out")]
+        if let (true, Some((module, info))) = (args.gen_msl, retained) {
+            match naga::back::msl::write_string(
+                module,
+                info,
+                &naga::back::msl::Options::default(),
+                &naga::back::msl::PipelineOptions::default(),
+            ) {
+                Ok((source, _)) => items.push(syn::parse_quote! {
+                    #[doc = "The shader translated to the Metal Shading Language, with overrides set to their default values."]
+                    pub const MSL: &'static str = #source;
+                }),
+                Err(error) => diagnostics.warn(format!(
+                    "failed to write {} as MSL, so no `MSL` was generated: {}",
+                    describe(entry_point),
+                    error
+                )),
+            }
+        }
+    }
+
+    items
+}
@@ -0,0 +1,288 @@
+use std::path::{Path, PathBuf};
+
+use crate::{ModuleSource, ModuleToTokens, ModuleToTokensConfig};
+
+/// Something that went wrong while generating Rust definitions for a shader.
+#[derive(Debug)]
+pub enum BuildError {
+    /// The output directory wasn't given, and `OUT_DIR` isn't set because we aren't running in a build script.
+    NoOutDir,
+    /// A shader couldn't be read, or its generated definitions couldn't be written.
+    Io {
+        /// The file being read or written.
+        path: PathBuf,
+        /// The underlying error.
+        error: std::io::Error,
+    },
+    /// A shader couldn't be parsed.
+    Parse {
+        /// The shader being parsed.
+        path: PathBuf,
+        /// The parse error, rendered against the shader's source.
+        message: String,
+    },
+    /// A shader failed validation, so its generated definitions would be incomplete.
+    Validation {
+        /// The shader being validated.
+        path: PathBuf,
+        /// The validation error, rendered against the shader's source.
+        message: String,
+    },
+    /// Two shaders would have their generated definitions written to the same file.
+    DuplicateOutput {
+        /// The file which both shaders would be written to.
+        path: PathBuf,
+        /// The two shaders.
+        shaders: [PathBuf; 2],
+    },
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::NoOutDir => {
+                write!(f, "no output directory was given, and `OUT_DIR` isn't set")
+            }
+            BuildError::Io { path, error } => write!(f, "`{}`: {}", path.display(), error),
+            BuildError::Parse { path, message } => {
+                write!(f, "failed to parse `{}`:\n{}", path.display(), message)
+            }
+            BuildError::Validation { path, message } => {
+                write!(f, "failed to validate `{}`:\n{}", path.display(), message)
+            }
+            BuildError::DuplicateOutput {
+                path,
+                shaders: [a, b],
+            } => write!(
+                f,
+                "`{}` and `{}` would both be written to `{}`",
+                a.display(),
+                b.display(),
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Generates Rust definitions for a set of shaders from a build script, writing one pretty-printed `.rs` file per
+/// shader into `OUT_DIR`. WGSL shaders' files are named after their file stem, so `compute.wgsl` gives `compute.rs`,
+/// while other shaders keep their extension, so `shader.vert` and `shader.frag` give `shader.vert.rs` and
+/// `shader.frag.rs`.
+///
+/// Shaders are parsed as WGSL, unless the `spv-in` feature is enabled and they have the `.spv` extension, or the
+/// `glsl-in` feature is enabled and they have one of the `.vert`, `.frag` or `.comp` extensions.
+///
+/// # Usage
+///
+/// ```no_run
+/// // build.rs
+/// naga_to_tokenstream::build::Builder::new()
+///     .shader("shaders/compute.wgsl")
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// The generated definitions can then be included in a module:
+///
+/// ```ignore
+/// mod compute {
+///     include!(concat!(env!("OUT_DIR"), "/compute.rs"));
+/// }
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    shaders: Vec<PathBuf>,
+    config: ModuleToTokensConfig,
+    out_dir: Option<PathBuf>,
+    defines: Vec<(String, String)>,
+}
+
+impl Builder {
+    /// Constructs a builder with no shaders and the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a shader to generate definitions for, in a language chosen by its extension. Relative paths are relative
+    /// to the crate root, as this is the working directory of build scripts.
+    pub fn shader(mut self, path: impl Into<PathBuf>) -> Self {
+        self.shaders.push(path.into());
+        self
+    }
+
+    /// Adds a collection of shaders to generate definitions for, each in a language chosen by its extension.
+    pub fn shaders(mut self, paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        self.shaders.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the configuration used to generate the definitions for every shader.
+    pub fn config(mut self, config: ModuleToTokensConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Adds a preprocessor definition used when parsing GLSL shaders, akin to `#define name value`.
+    pub fn define(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.defines.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the directory to write generated definitions to, instead of `OUT_DIR`.
+    pub fn out_dir(mut self, out_dir: impl Into<PathBuf>) -> Self {
+        self.out_dir = Some(out_dir.into());
+        self
+    }
+
+    /// Generates the definitions for every shader, writing them to the output directory and telling Cargo to rerun
+    /// the build script if any of the shaders change. Any warnings are passed on to Cargo. Gives the paths of the
+    /// files written, in the order the shaders were given.
+    pub fn build(self) -> Result<Vec<PathBuf>, BuildError> {
+        let out_dir = match self.out_dir {
+            Some(out_dir) => out_dir,
+            None => std::env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or(BuildError::NoOutDir)?,
+        };
+
+        // Check every output is distinct before writing any of them
+        let outputs: Vec<_> = self
+            .shaders
+            .iter()
+            .map(|shader| output_path(&out_dir, shader))
+            .collect();
+        for (i, output) in outputs.iter().enumerate() {
+            if let Some(j) = outputs[..i].iter().position(|other| other == output) {
+                return Err(BuildError::DuplicateOutput {
+                    path: output.clone(),
+                    shaders: [self.shaders[j].clone(), self.shaders[i].clone()],
+                });
+            }
+        }
+
+        for (shader, output) in self.shaders.iter().zip(&outputs) {
+            println!("cargo:rerun-if-changed={}", shader.display());
+
+            let generated = generate(shader, &self.defines, self.config.clone())?;
+            write_if_changed(output, &generated)?;
+        }
+
+        Ok(outputs)
+    }
+}
+
+/// Gives the file which a shader's generated definitions are written to. Only the `.wgsl` extension is dropped, so
+/// that shaders of other languages which differ only by extension, such as GLSL stages, don't overwrite each other.
+fn output_path(out_dir: &Path, shader: &Path) -> PathBuf {
+    let name = match shader.extension() {
+        Some(extension) if extension != "wgsl" => shader.file_name(),
+        _ => shader.file_stem(),
+    };
+    let mut name = name.unwrap_or(shader.as_os_str()).to_owned();
+    name.push(".rs");
+    out_dir.join(name)
+}
+
+/// Reads and parses a single shader, choosing its language from its extension. Gives the module along with its source
+/// code, if it was text.
+#[cfg_attr(not(feature = "glsl-in"), allow(unused_variables))]
+fn parse(
+    path: &Path,
+    defines: &[(String, String)],
+) -> Result<(naga::Module, Option<String>), BuildError> {
+    let read_error = |error| BuildError::Io {
+        path: path.to_owned(),
+        error,
+    };
+
+    #[cfg(feature = "spv-in")]
+    if crate::front::is_spirv_path(path) {
+        let data = std::fs::read(path).map_err(read_error)?;
+        let module = crate::front::parse_spirv(&data).map_err(|error| BuildError::Parse {
+            path: path.to_owned(),
+            message: error.to_string(),
+        })?;
+        return Ok((module, None));
+    }
+
+    let source = std::fs::read_to_string(path).map_err(read_error)?;
+
+    #[cfg(feature = "glsl-in")]
+    if let Some(stage) = crate::front::glsl_stage_from_path(path) {
+        let module =
+            crate::front::parse_glsl(&source, stage, defines.iter().cloned()).map_err(|error| {
+                BuildError::Parse {
+                    path: path.to_owned(),
+                    message: error.emit_to_string(&source),
+                }
+            })?;
+        return Ok((module, Some(source)));
+    }
+
+    let module = naga::front::wgsl::parse_str(&source).map_err(|error| BuildError::Parse {
+        path: path.to_owned(),
+        message: error.emit_to_string_with_path(&source, path),
+    })?;
+    Ok((module, Some(source)))
+}
+
+/// Reads, parses, validates and converts a single shader, giving the pretty-printed Rust definitions.
+fn generate(
+    path: &Path,
+    defines: &[(String, String)],
+    mut config: ModuleToTokensConfig,
+) -> Result<String, BuildError> {
+    let (module, source) = parse(path, defines)?;
+
+    config.source = source.map(|code| ModuleSource {
+        code,
+        path: Some(path.to_owned()),
+        embed_verbatim: false,
+    });
+    // The module is validated while converting it, which is the only source of errors
+    let (items, diagnostics) = module.to_items_with_diagnostics(config);
+    if diagnostics.has_errors() {
+        let errors: Vec<_> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == crate::diagnostics::Severity::Error)
+            .map(ToString::to_string)
+            .collect();
+        return Err(BuildError::Validation {
+            path: path.to_owned(),
+            message: errors.join("\n"),
+        });
+    }
+    for diagnostic in &diagnostics {
+        // Cargo only shows the first line of each warning
+        println!(
+            "cargo:warning={}",
+            diagnostic.to_string().replace('\n', " ")
+        );
+    }
+
+    Ok(prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: vec![],
+        items,
+    }))
+}
+
+/// Writes a file, unless it already has the given contents, so that crates including it aren't rebuilt needlessly.
+fn write_if_changed(path: &Path, contents: &str) -> Result<(), BuildError> {
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(());
+    }
+    std::fs::write(path, contents).map_err(|error| BuildError::Io {
+        path: path.to_owned(),
+        error,
+    })
+}
@@ -0,0 +1,618 @@
+use std::collections::HashMap;
+
+use naga::valid::Capabilities;
+
+use crate::ModuleToTokensConfig;
+
+/// The `wgpu::Features` which allow each capability, mirroring how `wgpu` chooses the capabilities it validates
+/// shaders with. Capabilities which aren't listed are instead given by `wgpu::DownlevelFlags`, or aren't supported by
+/// `wgpu`.
+const CAPABILITY_FEATURES: &[(Capabilities, &str)] = &[
+    (Capabilities::PUSH_CONSTANT, "PUSH_CONSTANTS"),
+    (Capabilities::FLOAT64, "SHADER_F64"),
+    (Capabilities::PRIMITIVE_INDEX, "SHADER_PRIMITIVE_INDEX"),
+    (
+        Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+        "SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING",
+    ),
+    (
+        Capabilities::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+        "UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING",
+    ),
+    (
+        Capabilities::SAMPLER_NON_UNIFORM_INDEXING,
+        "SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING",
+    ),
+    (
+        Capabilities::STORAGE_TEXTURE_16BIT_NORM_FORMATS,
+        "TEXTURE_FORMAT_16BIT_NORM",
+    ),
+    (Capabilities::MULTIVIEW, "MULTIVIEW"),
+    (Capabilities::EARLY_DEPTH_TEST, "SHADER_EARLY_DEPTH_TEST"),
+    (Capabilities::SHADER_INT64, "SHADER_INT64"),
+    (
+        Capabilities::SHADER_INT64_ATOMIC_MIN_MAX,
+        "SHADER_INT64_ATOMIC_MIN_MAX",
+    ),
+    (
+        Capabilities::SHADER_INT64_ATOMIC_ALL_OPS,
+        "SHADER_INT64_ATOMIC_ALL_OPS",
+    ),
+    (Capabilities::TEXTURE_ATOMIC, "TEXTURE_ATOMIC"),
+    (Capabilities::TEXTURE_INT64_ATOMIC, "TEXTURE_INT64_ATOMIC"),
+    (Capabilities::SHADER_FLOAT32_ATOMIC, "SHADER_FLOAT32_ATOMIC"),
+    (Capabilities::DUAL_SOURCE_BLENDING, "DUAL_SOURCE_BLENDING"),
+    (Capabilities::SUBGROUP, "SUBGROUP"),
+    (Capabilities::SUBGROUP_BARRIER, "SUBGROUP_BARRIER"),
+    (Capabilities::RAY_QUERY, "EXPERIMENTAL_RAY_QUERY"),
+    (Capabilities::SUBGROUP_VERTEX_STAGE, "SUBGROUP_VERTEX"),
+];
+
+/// The capabilities allowing non-uniform indexing of binding arrays, which depend on the validator's uniformity
+/// analysis.
+const NON_UNIFORM_INDEXING: Capabilities =
+    Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+        .union(Capabilities::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING)
+        .union(Capabilities::SAMPLER_NON_UNIFORM_INDEXING);
+
+/// Finds the capabilities which a validated module can't pass validation without, using the info from validating it.
+/// Most are found by scanning the types, globals, entry point interfaces and statements which the validator checks.
+/// Non-uniform indexing depends on the validator's uniformity analysis, so is instead found by validating the module
+/// without each of those capabilities, only if the module has any binding arrays.
+fn required_capabilities(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    args: &ModuleToTokensConfig,
+) -> Capabilities {
+    let mut capabilities = Capabilities::empty();
+
+    for (_, ty) in module.types.iter() {
+        capabilities |= type_capabilities(&ty.inner);
+    }
+
+    for (_, global) in module.global_variables.iter() {
+        capabilities |= global_capabilities(global, module);
+    }
+
+    for (_, expression) in module.global_expressions.iter() {
+        capabilities |= expression_capabilities(expression);
+    }
+
+    let function_capabilities: HashMap<_, _> = module
+        .functions
+        .iter()
+        .map(|(handle, function)| {
+            let capabilities = function_capabilities(function, &info[handle], module);
+            (handle, capabilities)
+        })
+        .collect();
+    capabilities |= function_capabilities
+        .values()
+        .fold(Capabilities::empty(), |all, &capabilities| {
+            all | capabilities
+        });
+
+    for (index, entry_point) in module.entry_points.iter().enumerate() {
+        let mut entry_point_capabilities =
+            function_capabilities_of_entry_point(entry_point, info.get_entry_point(index), module);
+
+        // Subgroup operations are only allowed in vertex shaders by an extra capability
+        if entry_point.stage == naga::ShaderStage::Vertex {
+            let reachable = crate::reachability::Reachable::from_entry_point(module, entry_point);
+            for function in reachable.functions {
+                entry_point_capabilities |= function_capabilities[&function];
+            }
+            if entry_point_capabilities.contains(Capabilities::SUBGROUP) {
+                entry_point_capabilities |= Capabilities::SUBGROUP_VERTEX_STAGE;
+            }
+        }
+
+        capabilities |= entry_point_capabilities;
+    }
+
+    // Every 64-bit atomic operation is allowed by the greater capability, so the lesser isn't needed alongside it
+    if capabilities.contains(Capabilities::SHADER_INT64_ATOMIC_ALL_OPS) {
+        capabilities.remove(Capabilities::SHADER_INT64_ATOMIC_MIN_MAX);
+    }
+
+    capabilities | non_uniform_indexing_capabilities(module, args)
+}
+
+/// Gives the capabilities needed to use a scalar type.
+fn scalar_capabilities(scalar: naga::Scalar) -> Capabilities {
+    match (scalar.kind, scalar.width) {
+        (naga::ScalarKind::Float, 8) => Capabilities::FLOAT64,
+        (naga::ScalarKind::Sint | naga::ScalarKind::Uint, 8) => Capabilities::SHADER_INT64,
+        _ => Capabilities::empty(),
+    }
+}
+
+/// Gives the capabilities needed to declare a type. 64-bit atomics need at least `SHADER_INT64_ATOMIC_MIN_MAX`, and
+/// the operations on them may need more.
+fn type_capabilities(inner: &naga::TypeInner) -> Capabilities {
+    match *inner {
+        naga::TypeInner::Scalar(scalar)
+        | naga::TypeInner::Vector { scalar, .. }
+        | naga::TypeInner::Matrix { scalar, .. }
+        | naga::TypeInner::ValuePointer { scalar, .. } => scalar_capabilities(scalar),
+        naga::TypeInner::Atomic(scalar) => {
+            scalar_capabilities(scalar)
+                | match scalar {
+                    naga::Scalar::I64 | naga::Scalar::U64 => {
+                        Capabilities::SHADER_INT64_ATOMIC_MIN_MAX
+                    }
+                    naga::Scalar::F32 => Capabilities::SHADER_FLOAT32_ATOMIC,
+                    _ => Capabilities::empty(),
+                }
+        }
+        naga::TypeInner::Image {
+            dim: naga::ImageDimension::Cube,
+            arrayed: true,
+            ..
+        } => Capabilities::CUBE_ARRAY_TEXTURES,
+        naga::TypeInner::AccelerationStructure | naga::TypeInner::RayQuery => {
+            Capabilities::RAY_QUERY
+        }
+        _ => Capabilities::empty(),
+    }
+}
+
+/// Gives the capabilities needed to declare a global variable, beyond those of its type.
+fn global_capabilities(global: &naga::GlobalVariable, module: &naga::Module) -> Capabilities {
+    let ty = match module.types[global.ty].inner {
+        naga::TypeInner::BindingArray { base, .. } => base,
+        _ => global.ty,
+    };
+    match (global.space, &module.types[ty].inner) {
+        (naga::AddressSpace::PushConstant, _) => Capabilities::PUSH_CONSTANT,
+        (
+            naga::AddressSpace::Handle,
+            naga::TypeInner::Image {
+                class:
+                    naga::ImageClass::Storage {
+                        format:
+                            naga::StorageFormat::R16Unorm
+                            | naga::StorageFormat::R16Snorm
+                            | naga::StorageFormat::Rg16Unorm
+                            | naga::StorageFormat::Rg16Snorm
+                            | naga::StorageFormat::Rgba16Unorm
+                            | naga::StorageFormat::Rgba16Snorm,
+                        ..
+                    },
+                ..
+            },
+        ) => Capabilities::STORAGE_TEXTURE_16BIT_NORM_FORMATS,
+        _ => Capabilities::empty(),
+    }
+}
+
+/// Gives the capabilities needed by the scalar type of a literal or conversion, which needn't be in the type arena.
+fn expression_capabilities(expression: &naga::Expression) -> Capabilities {
+    match *expression {
+        naga::Expression::Literal(literal) => scalar_capabilities(literal.scalar()),
+        naga::Expression::As {
+            kind,
+            convert: Some(width),
+            ..
+        } => scalar_capabilities(naga::Scalar { kind, width }),
+        _ => Capabilities::empty(),
+    }
+}
+
+/// Gives the capabilities needed by the expressions and statements of a function, not including the functions it
+/// calls.
+fn function_capabilities(
+    function: &naga::Function,
+    info: &naga::valid::FunctionInfo,
+    module: &naga::Module,
+) -> Capabilities {
+    let expressions = function
+        .expressions
+        .iter()
+        .fold(Capabilities::empty(), |all, (_, expression)| {
+            all | expression_capabilities(expression)
+        });
+    expressions | block_capabilities(&function.body, info, module)
+}
+
+/// Gives the capabilities needed by an entry point's function and interface, not including the functions it calls.
+fn function_capabilities_of_entry_point(
+    entry_point: &naga::EntryPoint,
+    info: &naga::valid::FunctionInfo,
+    module: &naga::Module,
+) -> Capabilities {
+    let mut capabilities = function_capabilities(&entry_point.function, info, module);
+    if entry_point.early_depth_test.is_some() {
+        capabilities |= Capabilities::EARLY_DEPTH_TEST;
+    }
+
+    // Bindings are either given directly, or on the members of a struct
+    let arguments = entry_point
+        .function
+        .arguments
+        .iter()
+        .map(|argument| (argument.ty, argument.binding.as_ref()));
+    let result = entry_point
+        .function
+        .result
+        .iter()
+        .map(|result| (result.ty, result.binding.as_ref()));
+    for (ty, binding) in arguments.chain(result) {
+        match (binding, &module.types[ty].inner) {
+            (Some(binding), _) => capabilities |= binding_capabilities(binding),
+            (None, naga::TypeInner::Struct { members, .. }) => {
+                for binding in members.iter().filter_map(|member| member.binding.as_ref()) {
+                    capabilities |= binding_capabilities(binding);
+                }
+            }
+            (None, _) => {}
+        }
+    }
+
+    capabilities
+}
+
+/// Gives the capabilities needed by a binding in an entry point's interface.
+fn binding_capabilities(binding: &naga::Binding) -> Capabilities {
+    match *binding {
+        naga::Binding::BuiltIn(built_in) => match built_in {
+            naga::BuiltIn::ClipDistance => Capabilities::CLIP_DISTANCE,
+            naga::BuiltIn::CullDistance => Capabilities::CULL_DISTANCE,
+            naga::BuiltIn::PrimitiveIndex => Capabilities::PRIMITIVE_INDEX,
+            naga::BuiltIn::ViewIndex => Capabilities::MULTIVIEW,
+            naga::BuiltIn::SampleIndex => Capabilities::MULTISAMPLED_SHADING,
+            naga::BuiltIn::NumSubgroups
+            | naga::BuiltIn::SubgroupId
+            | naga::BuiltIn::SubgroupSize
+            | naga::BuiltIn::SubgroupInvocationId => Capabilities::SUBGROUP,
+            _ => Capabilities::empty(),
+        },
+        naga::Binding::Location {
+            second_blend_source,
+            sampling,
+            ..
+        } => {
+            let mut capabilities = Capabilities::empty();
+            if second_blend_source {
+                capabilities |= Capabilities::DUAL_SOURCE_BLENDING;
+            }
+            if sampling == Some(naga::Sampling::Sample) {
+                capabilities |= Capabilities::MULTISAMPLED_SHADING;
+            }
+            capabilities
+        }
+    }
+}
+
+/// Gives the capabilities needed by the statements within a block, using the types of the function's expressions
+/// from its info.
+fn block_capabilities(
+    block: &naga::Block,
+    info: &naga::valid::FunctionInfo,
+    module: &naga::Module,
+) -> Capabilities {
+    let mut capabilities = Capabilities::empty();
+    for statement in block.iter() {
+        capabilities |= match statement {
+            naga::Statement::Block(block) => block_capabilities(block, info, module),
+            naga::Statement::If { accept, reject, .. } => {
+                block_capabilities(accept, info, module) | block_capabilities(reject, info, module)
+            }
+            naga::Statement::Switch { cases, .. } => {
+                cases.iter().fold(Capabilities::empty(), |all, case| {
+                    all | block_capabilities(&case.body, info, module)
+                })
+            }
+            naga::Statement::Loop {
+                body, continuing, ..
+            } => {
+                block_capabilities(body, info, module)
+                    | block_capabilities(continuing, info, module)
+            }
+            naga::Statement::Barrier(barrier) if barrier.contains(naga::Barrier::SUB_GROUP) => {
+                Capabilities::SUBGROUP | Capabilities::SUBGROUP_BARRIER
+            }
+            naga::Statement::SubgroupBallot { .. }
+            | naga::Statement::SubgroupGather { .. }
+            | naga::Statement::SubgroupCollectiveOperation { .. } => Capabilities::SUBGROUP,
+            naga::Statement::Atomic {
+                pointer,
+                fun,
+                result,
+                ..
+            } => {
+                let naga::TypeInner::Pointer { base, space } =
+                    *info[*pointer].ty.inner_with(&module.types)
+                else {
+                    continue;
+                };
+                match module.types[base].inner {
+                    // Only the minimum and maximum of storage values, without giving the result, are allowed with
+                    // the lesser 64-bit atomic capability
+                    naga::TypeInner::Atomic(naga::Scalar::I64 | naga::Scalar::U64) => {
+                        let min_max =
+                            matches!(fun, naga::AtomicFunction::Min | naga::AtomicFunction::Max)
+                                && matches!(space, naga::AddressSpace::Storage { .. })
+                                && result.is_none();
+                        match min_max {
+                            true => Capabilities::SHADER_INT64_ATOMIC_MIN_MAX,
+                            false => Capabilities::SHADER_INT64_ATOMIC_ALL_OPS,
+                        }
+                    }
+                    _ => Capabilities::empty(),
+                }
+            }
+            naga::Statement::ImageAtomic { image, .. } => {
+                match *info[*image].ty.inner_with(&module.types) {
+                    naga::TypeInner::Image {
+                        class:
+                            naga::ImageClass::Storage {
+                                format: naga::StorageFormat::R64Uint,
+                                ..
+                            },
+                        ..
+                    } => Capabilities::TEXTURE_INT64_ATOMIC,
+                    naga::TypeInner::Image { .. } => Capabilities::TEXTURE_ATOMIC,
+                    _ => Capabilities::empty(),
+                }
+            }
+            _ => Capabilities::empty(),
+        };
+    }
+    capabilities
+}
+
+/// Finds the non-uniform indexing capabilities which a module can't pass validation without, by validating it
+/// without each of them. These only apply to binding arrays, so nothing is validated for modules without any.
+fn non_uniform_indexing_capabilities(
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+) -> Capabilities {
+    let has_binding_arrays = module.global_variables.iter().any(|(_, global)| {
+        matches!(
+            module.types[global.ty].inner,
+            naga::TypeInner::BindingArray { .. }
+        )
+    });
+    let validates_without = |capabilities| {
+        naga::valid::Validator::new(
+            args.validation.flags,
+            Capabilities::all().difference(capabilities),
+        )
+        .validate(module)
+        .is_ok()
+    };
+
+    // Most shaders index uniformly, which can be checked at once
+    if !has_binding_arrays || validates_without(NON_UNIFORM_INDEXING) {
+        return Capabilities::empty();
+    }
+    NON_UNIFORM_INDEXING
+        .iter()
+        .filter(|&capability| !validates_without(capability))
+        .collect()
+}
+
+/// Gives the `wgpu::Features` needed to use a storage texture with the given format and access, beyond those given
+/// by capabilities. Formats outside of the WebGPU core set need adapter-specific format features, as does
+/// read-write access to any format other than the 32-bit single channel ones.
+fn storage_texture_features(
+    format: naga::StorageFormat,
+    access: naga::StorageAccess,
+) -> Vec<&'static str> {
+    use naga::StorageFormat as F;
+
+    let mut features = Vec::new();
+    match format {
+        F::Rgba8Unorm
+        | F::Rgba8Snorm
+        | F::Rgba8Uint
+        | F::Rgba8Sint
+        | F::Rgba16Uint
+        | F::Rgba16Sint
+        | F::Rgba16Float
+        | F::R32Uint
+        | F::R32Sint
+        | F::R32Float
+        | F::Rg32Uint
+        | F::Rg32Sint
+        | F::Rg32Float
+        | F::Rgba32Uint
+        | F::Rgba32Sint
+        | F::Rgba32Float => {}
+        F::Bgra8Unorm => features.push("BGRA8UNORM_STORAGE"),
+        F::R64Uint => features.push("TEXTURE_INT64_ATOMIC"),
+        F::R16Unorm
+        | F::R16Snorm
+        | F::Rg16Unorm
+        | F::Rg16Snorm
+        | F::Rgba16Unorm
+        | F::Rgba16Snorm => features.push("TEXTURE_FORMAT_16BIT_NORM"),
+        _ => features.push("TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES"),
+    }
+
+    let read_write = access.contains(naga::StorageAccess::LOAD | naga::StorageAccess::STORE);
+    if read_write && !matches!(format, F::R32Uint | F::R32Sint | F::R32Float) {
+        features.push("TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES");
+    }
+
+    features
+}
+
+/// Finds the `wgpu::Features` which a module needs, given the capabilities it requires. Along with the features
+/// allowing those capabilities, the module's global variables are checked for binding arrays, storage texture formats
+/// and acceleration structures, which `wgpu` checks when creating pipelines rather than validating shaders.
+fn required_features(module: &naga::Module, capabilities: Capabilities) -> Vec<&'static str> {
+    let mut features = Vec::new();
+    let mut require = |feature| {
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+    };
+
+    for &(capability, feature) in CAPABILITY_FEATURES {
+        if capabilities.contains(capability) {
+            require(feature);
+        }
+    }
+
+    for (_, global) in module.global_variables.iter() {
+        let (ty, is_binding_array) = match module.types[global.ty].inner {
+            naga::TypeInner::BindingArray { base, .. } => (base, true),
+            _ => (global.ty, false),
+        };
+        match module.types[ty].inner {
+            naga::TypeInner::Image {
+                class: naga::ImageClass::Storage { format, access },
+                ..
+            } => {
+                if is_binding_array {
+                    require("TEXTURE_BINDING_ARRAY");
+                    require("STORAGE_RESOURCE_BINDING_ARRAY");
+                }
+                for feature in storage_texture_features(format, access) {
+                    require(feature);
+                }
+            }
+            naga::TypeInner::Image { .. } | naga::TypeInner::Sampler { .. } => {
+                if is_binding_array {
+                    require("TEXTURE_BINDING_ARRAY");
+                }
+            }
+            naga::TypeInner::AccelerationStructure => {
+                require("EXPERIMENTAL_RAY_TRACING_ACCELERATION_STRUCTURE");
+            }
+            _ => {
+                if is_binding_array {
+                    require("BUFFER_BINDING_ARRAY");
+                    if let naga::AddressSpace::Storage { .. } = global.space {
+                        require("STORAGE_RESOURCE_BINDING_ARRAY");
+                    }
+                }
+            }
+        }
+    }
+
+    features
+}
+
+/// Gives an expression combining the named flags of a bitflags type, such as `Type::A.union(Type::B)`.
+fn flags_to_tokens<'a>(
+    ty: proc_macro2::TokenStream,
+    names: impl IntoIterator<Item = &'a str>,
+) -> proc_macro2::TokenStream {
+    let mut names = names
+        .into_iter()
+        .map(|name| syn::Ident::new(name, proc_macro2::Span::call_site()));
+    match names.next() {
+        Some(first) => {
+            let rest = names.collect::<Vec<_>>();
+            quote::quote! { #ty::#first #(.union(#ty::#rest))* }
+        }
+        None => quote::quote! { #ty::empty() },
+    }
+}
+
+/// Gives constants describing the capabilities and `wgpu::Features` which a validated module requires, using the info
+/// from validating it. For an entry point, the module should retain only that entry point and what it reaches, so that
+/// only the functions and global variables it can reach are considered.
+pub(crate) fn make_requirements(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    is_entry_point: bool,
+    args: &ModuleToTokensConfig,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+    if !(args.gen_naga || args.gen_wgpu) {
+        return items;
+    }
+
+    let described = match is_entry_point {
+        true => "this entry point",
+        false => "the shader",
+    };
+
+    let capabilities = required_capabilities(module, info, args);
+
+    if args.gen_naga {
+        let doc = format!(
+            "The capabilities which a `naga::valid::Validator` must allow for {} to pass validation.",
+            described
+        );
+        let value = flags_to_tokens(
+            quote::quote!(naga::valid::Capabilities),
+            capabilities.iter_names().map(|(name, _)| name),
+        );
+        items.push(syn::parse_quote! {
+            #[doc = #doc]
+            pub const REQUIRED_CAPABILITIES: naga::valid::Capabilities = #value;
+        });
+    }
+
+    if args.gen_wgpu {
+        let doc = format!(
+            "The `wgpu::Features` which a device must have enabled to create pipelines using {}. Some capabilities are instead given by `wgpu::DownlevelFlags`, so aren't included.",
+            described
+        );
+        let value = flags_to_tokens(
+            quote::quote!(wgpu::Features),
+            required_features(module, capabilities),
+        );
+        items.push(syn::parse_quote! {
+            #[doc = #doc]
+            pub const REQUIRED_FEATURES: wgpu::Features = #value;
+        });
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feature_names_exist_in_wgpu() {
+        let module = naga::front::wgsl::parse_str(
+            r#"
+        struct Buffer {
+            data: array<f32>,
+        }
+
+        @group(0) @binding(0) var bgra: texture_storage_2d<bgra8unorm, write>;
+        @group(0) @binding(1) var norm: texture_storage_2d<rg16snorm, read_write>;
+        @group(0) @binding(2) var wide: texture_storage_2d<r64uint, read_write>;
+        @group(0) @binding(3) var packed: texture_storage_2d<rgb10a2unorm, write>;
+        @group(0) @binding(4) var storage_textures: binding_array<texture_storage_2d<r32float, write>, 2>;
+        @group(0) @binding(5) var textures: binding_array<texture_2d<f32>, 2>;
+        @group(0) @binding(6) var<storage> buffers: binding_array<Buffer, 2>;
+        @group(0) @binding(7) var<uniform> uniforms: binding_array<vec4<f32>, 2>;
+        @group(0) @binding(8) var scene: acceleration_structure;
+        "#,
+        )
+        .unwrap();
+
+        // Every capability brings in the whole table, and the globals cover the features needed by bindings
+        let features = required_features(&module, Capabilities::all());
+        for (_, feature) in CAPABILITY_FEATURES {
+            assert!(features.contains(feature));
+        }
+        for feature in [
+            "BGRA8UNORM_STORAGE",
+            "TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES",
+            "TEXTURE_BINDING_ARRAY",
+            "STORAGE_RESOURCE_BINDING_ARRAY",
+            "BUFFER_BINDING_ARRAY",
+            "EXPERIMENTAL_RAY_TRACING_ACCELERATION_STRUCTURE",
+        ] {
+            assert!(features.contains(&feature), "{} wasn't required", feature);
+        }
+        for feature in features {
+            assert!(
+                wgpu_types::Features::from_name(feature).is_some(),
+                "`{}` isn't a `wgpu::Features` flag",
+                feature
+            );
+        }
+    }
+}
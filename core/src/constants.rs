@@ -0,0 +1,298 @@
+use crate::{
+    diagnostics::Diagnostics, expressions::ConstValue, types::TypesDefinitions,
+    ModuleToTokensConfig,
+};
+
+pub(crate) fn literal_to_tokens(lit: &naga::Literal) -> proc_macro2::TokenStream {
+    match lit {
+        naga::Literal::F64(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::F32(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::U32(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::U64(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::I32(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::Bool(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::I64(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::AbstractInt(v) => quote::quote! {
+            #v
+        },
+        naga::Literal::AbstractFloat(v) => quote::quote! {
+            #v
+        },
+    }
+}
+
+/// Converts a scalar value into a Rust literal of the given scalar type, reporting a diagnostic if the value
+/// can't be represented by that type.
+fn scalar_to_tokens(
+    lit: &naga::Literal,
+    scalar: naga::Scalar,
+    constant_name: &str,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Option<proc_macro2::TokenStream> {
+    if let Some(lit) = crate::expressions::concretize_literal(*lit, scalar) {
+        return Some(literal_to_tokens(&lit));
+    }
+
+    let ty = crate::types::rust_type(&naga::TypeInner::Scalar(scalar), args)?;
+    diagnostics.warn(format!(
+        "the value `{}` of the constant `{}` can't be represented as a `{}`, so no `VALUE` was generated",
+        crate::expressions::literal_to_string(*lit),
+        constant_name,
+        quote::quote!(#ty)
+    ));
+    None
+}
+
+/// Converts an evaluated value of a given type into a Rust expression of the equivalent Rust type, if representable.
+fn value_to_tokens(
+    value: &ConstValue,
+    ty: naga::Handle<naga::Type>,
+    module: &naga::Module,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    constant_name: &str,
+    diagnostics: &mut Diagnostics,
+) -> Option<proc_macro2::TokenStream> {
+    let ty_inner = &module.types.get_handle(ty).ok()?.inner;
+    match (value, ty_inner) {
+        (ConstValue::Scalar(lit), naga::TypeInner::Scalar(scalar)) => {
+            scalar_to_tokens(lit, *scalar, constant_name, args, diagnostics)
+        }
+        (ConstValue::Composite(components), naga::TypeInner::Vector { scalar, .. }) => {
+            let components = components
+                .iter()
+                .map(|component| match component {
+                    ConstValue::Scalar(lit) => {
+                        scalar_to_tokens(lit, *scalar, constant_name, args, diagnostics)
+                    }
+                    ConstValue::Composite(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            if args.gen_glam {
+                let ty_ident = types.rust_type_ident(ty, module, args)?;
+                Some(quote::quote! { #ty_ident::new(#(#components),*) })
+            } else {
+                Some(quote::quote! { [#(#components),*] })
+            }
+        }
+        (
+            ConstValue::Composite(columns),
+            naga::TypeInner::Matrix {
+                columns: _,
+                rows,
+                scalar,
+            },
+        ) => {
+            if !args.gen_glam {
+                return None;
+            }
+            let ty_ident = types.rust_type_ident(ty, module, args)?;
+            let column_ty = crate::types::rust_type(
+                &naga::TypeInner::Vector {
+                    size: *rows,
+                    scalar: *scalar,
+                },
+                args,
+            )?;
+            let columns = columns
+                .iter()
+                .map(|column| match column {
+                    ConstValue::Composite(components) => {
+                        let components = components
+                            .iter()
+                            .map(|component| match component {
+                                ConstValue::Scalar(lit) => {
+                                    scalar_to_tokens(lit, *scalar, constant_name, args, diagnostics)
+                                }
+                                ConstValue::Composite(_) => None,
+                            })
+                            .collect::<Option<Vec<_>>>()?;
+                        Some(quote::quote! { #column_ty::new(#(#components),*) })
+                    }
+                    ConstValue::Scalar(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(quote::quote! { #ty_ident::from_cols(#(#columns),*) })
+        }
+        (
+            ConstValue::Composite(elements),
+            naga::TypeInner::Array {
+                base,
+                size: naga::ArraySize::Constant(_),
+                ..
+            },
+        ) => {
+            let elements = elements
+                .iter()
+                .map(|element| {
+                    value_to_tokens(
+                        element,
+                        *base,
+                        module,
+                        types,
+                        args,
+                        constant_name,
+                        diagnostics,
+                    )
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(quote::quote! { [#(#elements),*] })
+        }
+        (ConstValue::Composite(values), naga::TypeInner::Struct { members, .. }) => {
+            let ty_ident = types.rust_type_ident(ty, module, args)?;
+            let member_names = crate::types::struct_member_idents(members);
+            let fields = values
+                .iter()
+                .zip(members)
+                .zip(member_names)
+                .map(|((value, member), member_name)| {
+                    let member_name = member_name?;
+                    let value = value_to_tokens(
+                        value,
+                        member.ty,
+                        module,
+                        types,
+                        args,
+                        constant_name,
+                        diagnostics,
+                    )?;
+                    Some(quote::quote! { #member_name: #value })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(quote::quote! { #ty_ident { #(#fields),* } })
+        }
+        _ => None,
+    }
+}
+
+fn make_constant_value(
+    constant: &naga::Constant,
+    module: &naga::Module,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Option<proc_macro2::TokenStream> {
+    let value = crate::expressions::evaluate(constant.init, module)?;
+    let constant_name = constant.name.as_deref().unwrap_or("<unnamed>");
+    value_to_tokens(
+        &value,
+        constant.ty,
+        module,
+        types,
+        args,
+        constant_name,
+        diagnostics,
+    )
+}
+
+/// Converts a constant in a module into a collection of Rust definitions including the type and value of the constant,
+/// if representable.
+pub fn make_constant(
+    constant: &naga::Constant,
+    module: &naga::Module,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    if let Some(name) = &constant.name {
+        items.push(syn::Item::Const(syn::parse_quote! {
+            pub const NAME: &'static str = #name;
+        }));
+    }
+
+    if let Ok(naga::Expression::Override(override_handle)) =
+        &module.global_expressions.try_get(constant.init)
+    {
+        if let Ok(naga::Override { name, id, .. }) = module.overrides.try_get(*override_handle) {
+            if let Some(id) = id {
+                items.push(syn::Item::Const(syn::parse_quote! {
+                    pub const OVERRIDE_ID: u32 = #id;
+                }));
+            }
+            if let Some(name) = name {
+                items.push(syn::Item::Const(syn::parse_quote! {
+                    pub const OVERRIDE_NAME: &'static str = #name;
+                }));
+            }
+        }
+    }
+
+    let ty_ident = types.rust_type_ident(constant.ty, module, args);
+    let value = make_constant_value(constant, module, types, args, diagnostics);
+    if let (Some(ty_ident), Some(value)) = (ty_ident, value) {
+        items.push(syn::Item::Const(syn::parse_quote! {
+            pub const VALUE: #ty_ident = #value ;
+        }));
+    }
+
+    items
+}
+
+/// Builds a collection of constants into a collection of Rust module definitions containing
+/// each of the constants properties, such as type and value.
+pub fn make_constants(
+    module: &naga::Module,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Vec<syn::Item> {
+    let mut constants = Vec::new();
+
+    for (constant_handle, constant) in module.constants.iter() {
+        // Get name for constant module
+        let constant_name = match &constant.name {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+        let constant_name_ident = syn::parse_str::<syn::Ident>(&constant_name);
+        let constant_name_ident = match constant_name_ident {
+            Ok(constant_name_ident) => constant_name_ident,
+            Err(_) => continue,
+        };
+
+        // Make items within module, with any problems pointing at the constant's declaration
+        let diagnostics_start = diagnostics.len();
+        let constant_items =
+            crate::collect_tokenstream(make_constant(constant, module, types, args, diagnostics));
+        let span = module.constants.get_span(constant_handle);
+        diagnostics.locate_since(diagnostics_start, crate::source_location(span, args));
+
+        // Collate into an inner module
+        let doc = format!(
+            "Information about the `{}` constant variable within this shader module.{}",
+            constant_name,
+            crate::declared_at(span, args)
+        );
+        let doc = args
+            .doc_comments
+            .item_attributes(&constant_name, Some(&doc));
+        constants.push(syn::parse_quote! {
+            #doc
+            pub mod #constant_name_ident {
+                #[allow(unused)]
+                use super::*;
+
+                #constant_items
+            }
+        })
+    }
+
+    constants
+}
@@ -0,0 +1,133 @@
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Some item could not be generated, but the rest of the output is still usable.
+    Warning,
+    /// The output is likely incomplete in a way that downstream code will depend on.
+    Error,
+}
+
+/// A position within the source code of a shader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The path of the file containing the source code, if known.
+    pub path: Option<std::path::PathBuf>,
+    /// The 1-based line number.
+    pub line: u32,
+    /// The 1-based column, in bytes.
+    pub column: u32,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}:{}", path.display(), self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column),
+        }
+    }
+}
+
+/// A message describing something that went wrong while converting a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the diagnostic is.
+    pub severity: Severity,
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// Where in the shader's source code the problem is, if the source was given.
+    pub location: Option<Location>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message)?,
+            Severity::Error => write!(f, "error: {}", self.message)?,
+        }
+        if let Some(location) = &self.location {
+            write!(f, "\n --> {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// A collection of diagnostics, built up while converting a module.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Constructs an empty collection of diagnostics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a warning.
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            location: None,
+        });
+    }
+
+    /// Records an error.
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            location: None,
+        });
+    }
+
+    /// Whether any errors have been recorded.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// Whether no diagnostics have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Iterates over the recorded diagnostics, in the order they were recorded.
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// The number of diagnostics recorded so far.
+    pub(crate) fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Attaches a location to every diagnostic recorded since there were `start` diagnostics, unless they already
+    /// have one.
+    pub(crate) fn locate_since(&mut self, start: usize, location: Option<Location>) {
+        for diagnostic in &mut self.diagnostics[start..] {
+            if diagnostic.location.is_none() {
+                diagnostic.location = location.clone();
+            }
+        }
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.iter()
+    }
+}
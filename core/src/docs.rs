@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+/// The `///` doc comments written on declarations within a WGSL shader, keyed by the names of the items they
+/// document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocComments {
+    /// Doc comments on module-scope items, such as structs, constants, overrides, globals and functions.
+    items: HashMap<String, Vec<String>>,
+    /// Doc comments on struct members, keyed by the struct name and then the member name.
+    members: HashMap<(String, String), Vec<String>>,
+}
+
+/// Removes any leading `@attribute` or `@attribute(...)` tokens from a line of WGSL.
+fn strip_attributes(mut code: &str) -> &str {
+    while let Some(rest) = code.strip_prefix('@') {
+        let name_end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let mut rest = rest[name_end..].trim_start();
+        if rest.starts_with('(') {
+            let mut depth = 0;
+            let mut end = rest.len();
+            for (i, c) in rest.char_indices() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = i + 1;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            rest = &rest[end..];
+        }
+        code = rest.trim_start();
+    }
+    code
+}
+
+/// Gives the identifier at the start of some WGSL code, if there is one.
+fn leading_ident(code: &str) -> Option<&str> {
+    let end = code
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(code.len());
+    let ident = &code[..end];
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(ident)
+}
+
+/// Gives the kind and name of the module-scope item declared at the start of some WGSL code, if any.
+fn declared_item(code: &str) -> Option<(&str, &str)> {
+    let keyword = leading_ident(code)?;
+    let mut rest = code[keyword.len()..].trim_start();
+    match keyword {
+        "struct" | "const" | "override" | "fn" | "alias" => {}
+        "var" => {
+            // Skip the address space and access mode, e.g. `var<storage, read_write>`
+            if let Some(after) = rest.strip_prefix('<') {
+                rest = after[after.find('>')? + 1..].trim_start();
+            }
+        }
+        _ => return None,
+    }
+    Some((keyword, leading_ident(rest)?))
+}
+
+impl DocComments {
+    /// Reads the doc comments from some WGSL source code. Each run of `///` comment lines is attached to the struct,
+    /// struct member, constant, override, global variable or function declared on the line that follows it,
+    /// skipping any lines containing only attributes. Block comments are skipped, including any `///` lines or braces
+    /// within them.
+    pub fn from_wgsl(source: &str) -> Self {
+        let mut docs = Self::default();
+
+        let mut pending = Vec::new();
+        let mut depth = 0usize;
+        let mut current_struct: Option<String> = None;
+
+        for line in crate::strip_comments(source, true).lines() {
+            let trimmed = line.trim();
+
+            if let Some(doc) = trimmed.strip_prefix("///") {
+                // Four or more slashes is an ordinary comment, as in Rust
+                if !doc.starts_with('/') {
+                    pending.push(doc.strip_prefix(' ').unwrap_or(doc).to_owned());
+                    continue;
+                }
+            }
+
+            // Ignore any trailing ordinary comment
+            let code = match trimmed.find("//") {
+                Some(comment_start) => trimmed[..comment_start].trim_end(),
+                None => trimmed,
+            };
+            let code = strip_attributes(code);
+            if code.is_empty() {
+                continue;
+            }
+
+            let docs_for_line = std::mem::take(&mut pending);
+            if depth == 0 {
+                if let Some((keyword, name)) = declared_item(code) {
+                    if keyword == "struct" {
+                        current_struct = Some(name.to_owned());
+                    }
+                    if !docs_for_line.is_empty() {
+                        docs.items.insert(name.to_owned(), docs_for_line);
+                    }
+                }
+            } else if let (1, Some(struct_name)) = (depth, &current_struct) {
+                if let Some(member_name) = leading_ident(code) {
+                    if code[member_name.len()..].trim_start().starts_with(':')
+                        && !docs_for_line.is_empty()
+                    {
+                        docs.members
+                            .insert((struct_name.clone(), member_name.to_owned()), docs_for_line);
+                    }
+                }
+            }
+
+            for c in code.chars() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            if depth == 0 {
+                current_struct = None;
+            }
+        }
+
+        docs
+    }
+
+    /// Gives the lines of the doc comment on the module-scope item with the given name, if it has one.
+    pub fn item(&self, name: &str) -> Option<&[String]> {
+        self.items.get(name).map(Vec::as_slice)
+    }
+
+    /// Gives the lines of the doc comment on a member of the struct with the given name, if it has one.
+    pub fn member(&self, struct_name: &str, member_name: &str) -> Option<&[String]> {
+        self.members
+            .get(&(struct_name.to_owned(), member_name.to_owned()))
+            .map(Vec::as_slice)
+    }
+
+    /// Gives `#[doc]` attributes carrying the doc comment on the module-scope item with the given name, followed by
+    /// the given generated documentation as a separate paragraph.
+    pub(crate) fn item_attributes(
+        &self,
+        name: &str,
+        generated: Option<&str>,
+    ) -> proc_macro2::TokenStream {
+        let lines = self.item(name).unwrap_or_default();
+        let mut attributes = doc_attributes(lines);
+        if let Some(generated) = generated {
+            if !lines.is_empty() {
+                attributes.extend(quote::quote!(#[doc = ""]));
+            }
+            attributes.extend(quote::quote!(#[doc = #generated]));
+        }
+        attributes
+    }
+
+    /// Gives `#[doc]` attributes carrying the doc comment on a member of the struct with the given name.
+    pub(crate) fn member_attributes(
+        &self,
+        struct_name: &str,
+        member_name: &str,
+    ) -> proc_macro2::TokenStream {
+        doc_attributes(self.member(struct_name, member_name).unwrap_or_default())
+    }
+}
+
+/// Converts lines of documentation into `#[doc]` attributes, as `///` comments would be in Rust.
+fn doc_attributes(lines: &[String]) -> proc_macro2::TokenStream {
+    let lines = lines.iter().map(|line| {
+        if line.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", line)
+        }
+    });
+    quote::quote! {
+        #(#[doc = #lines])*
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{diagnostics::Diagnostics, types::TypesDefinitions, ModuleToTokensConfig};
+
+/// Converts an entry point in a module into a collection of Rust definitions including the name and workgroup size
+/// of the entry point, if representable. Sources of the entry point, along with the globals it uses, are only generated
+/// if the info from validating the module is given.
+pub fn make_entry_point(
+    entry_point: &naga::EntryPoint,
+    module: &naga::Module,
+    info: Option<&naga::valid::ModuleInfo>,
+    _types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    // Entry point name
+    let name = &entry_point.name;
+    items.push(syn::Item::Const(syn::parse_quote! {
+        pub const NAME: &'static str = #name;
+    }));
+
+    // Stage
+    if args.gen_naga {
+        let stage = match entry_point.stage {
+            naga::ShaderStage::Vertex => quote::quote! { naga::ShaderStage::Vertex },
+            naga::ShaderStage::Fragment => quote::quote! { naga::ShaderStage::Fragment },
+            naga::ShaderStage::Compute => quote::quote! { naga::ShaderStage::Compute },
+        };
+        items.push(syn::Item::Const(syn::parse_quote! {
+            pub const STAGE: naga::ShaderStage = #stage;
+        }));
+    }
+
+    // Workgroup size
+    let x = entry_point.workgroup_size[0];
+    let y = entry_point.workgroup_size[1];
+    let z = entry_point.workgroup_size[2];
+    if let Some(size_overrides) = &entry_point.workgroup_size_overrides {
+        // The size depends on overrides, so can only be known once the user has chosen their override values
+        if let Some((workgroup_size, fallible)) =
+            make_workgroup_size_fn(entry_point, size_overrides, module, args)
+        {
+            items.push(workgroup_size);
+
+            if entry_point.stage == naga::ShaderStage::Compute {
+                items.extend(make_override_dispatch_helpers(fallible, args));
+            }
+        }
+    } else if x != 0 && y != 0 && z != 0 {
+        items.push(syn::Item::Const(syn::parse_quote! {
+            pub const WORKGROUP_SIZE: [u32; 3] = [#x, #y, #z];
+        }));
+
+        if entry_point.stage == naga::ShaderStage::Compute {
+            items.extend(make_dispatch_helpers(args));
+        }
+    }
+
+    // Sources, requirements and the globals kept in sources can only be generated for a module which passed validation
+    let Some(info) = info else {
+        return items;
+    };
+
+    // The module excluding all other entry points and anything only they use, shared between the sources below
+    let mut exclusive = module.clone();
+    crate::reachability::retain_reachable(&mut exclusive, &entry_point.name, diagnostics);
+
+    // The globals kept in the exclusive source, re-exported from the module-wide `globals` module
+    let globals = exclusive
+        .global_variables
+        .iter()
+        .filter_map(|(_, global)| syn::parse_str::<syn::Ident>(global.name.as_ref()?).ok())
+        .map(|ident| quote::quote! { pub use super::super::super::globals::#ident; });
+    items.push(syn::parse_quote! {
+        #[allow(unused)]
+        #[doc = "Information about the globals used by this entry point, which are the only globals kept in `EXCLUSIVE_SOURCE`. Pipelines created from `EXCLUSIVE_SOURCE` should have layouts matching these bindings."]
+        pub mod globals {
+            #(#globals)*
+        }
+    });
+
+    let describe = format!("the entry point `{}`", entry_point.name);
+    let retained = crate::derived_module_info(
+        &exclusive,
+        &describe,
+        "requirements, `EXCLUSIVE_SOURCE`, `HLSL` or `MSL` were",
+        args,
+        diagnostics,
+    )
+    .map(|info| (exclusive, info));
+
+    // The capabilities and features required by only this entry point
+    if let Some((module, info)) = &retained {
+        items.extend(crate::capabilities::make_requirements(
+            module, info, true, args,
+        ));
+    }
+
+    // The module sourcecode, excluding all other entry points and anything only they use
+    if let Some(src) = retained.as_ref().and_then(|(module, info)| {
+        crate::module_to_source(
+            module,
+            info,
+            &describe,
+            "EXCLUSIVE_SOURCE",
+            args,
+            diagnostics,
+        )
+    }) {
+        items.push(syn::parse_quote! {
+            #[doc = "The sourcecode for the shader, as a constant string, excluding any other entry points along with any functions, globals, constants, overrides and types which this entry point doesn't use."]
+            pub const EXCLUSIVE_SOURCE: &'static str = #src;
+        });
+    }
+
+    // The entry point in other shader languages
+    items.extend(crate::backends::make_backend_sources(
+        module,
+        info,
+        Some(entry_point),
+        retained.as_ref().map(|(module, info)| (module, info)),
+        args,
+        diagnostics,
+    ));
+
+    items
+}
+
+/// Generates a function which evaluates the workgroup size of an entry point whose `@workgroup_size` attribute
+/// depends on pipeline-overridable constants. The function gives `[u32; 3]` when evaluating the size can't fail, and
+/// `Option<[u32; 3]>` when the size is computed with integer arithmetic which may overflow or divide by zero. Along
+/// with the function, gives whether it is the latter.
+fn make_workgroup_size_fn(
+    entry_point: &naga::EntryPoint,
+    size_overrides: &[Option<naga::Handle<naga::Expression>>; 3],
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+) -> Option<(syn::Item, bool)> {
+    let overrides = quote::quote!(overrides);
+    let mut fallible = false;
+    let dimensions = size_overrides
+        .iter()
+        .zip(entry_point.workgroup_size)
+        .map(|(size_override, size)| match size_override {
+            Some(expr) => {
+                let (expr, may_fail) = crate::overrides::override_expression_to_tokens(
+                    *expr,
+                    &overrides,
+                    module,
+                    args,
+                    &quote::quote!(return None),
+                )?;
+                fallible |= may_fail;
+                Some(quote::quote! { #expr as u32 })
+            }
+            None => Some(quote::quote! { #size }),
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if fallible {
+        return Some((
+            syn::parse_quote! {
+                #[doc = "Evaluates the workgroup size of this entry point from the given pipeline-overridable constant values, using the default values given in the shader for any overrides left unset. Gives `None` if evaluating the size overflows or divides by zero, which WGSL treats as an error when creating a pipeline."]
+                #[allow(clippy::unnecessary_cast)]
+                pub fn workgroup_size(overrides: &overrides::Overrides) -> Option<[u32; 3]> {
+                    Some([#(#dimensions ,)*])
+                }
+            },
+            true,
+        ));
+    }
+
+    Some((
+        syn::parse_quote! {
+            #[doc = "Evaluates the workgroup size of this entry point from the given pipeline-overridable constant values, using the default values given in the shader for any overrides left unset."]
+            #[allow(clippy::unnecessary_cast)]
+            pub fn workgroup_size(overrides: &overrides::Overrides) -> [u32; 3] {
+                [#(#dimensions ,)*]
+            }
+        },
+        false,
+    ))
+}
+
+/// Generates helpers which convert a number of invocations into a number of workgroups to dispatch,
+/// using the `WORKGROUP_SIZE` constant of a compute entry point.
+fn make_dispatch_helpers(args: &ModuleToTokensConfig) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    items.push(syn::parse_quote! {
+        #[doc = "Gives the number of workgroups required to cover the given number of invocations in each dimension, rounding up."]
+        pub const fn dispatch_size(invocations: [u32; 3]) -> [u32; 3] {
+            [
+                invocations[0].div_ceil(WORKGROUP_SIZE[0]),
+                invocations[1].div_ceil(WORKGROUP_SIZE[1]),
+                invocations[2].div_ceil(WORKGROUP_SIZE[2]),
+            ]
+        }
+    });
+
+    if args.gen_wgpu {
+        items.push(syn::parse_quote! {
+            #[doc = "Dispatches enough workgroups on the given compute pass to cover the given number of invocations in each dimension."]
+            pub fn dispatch(pass: &mut wgpu::ComputePass<'_>, invocations: [u32; 3]) {
+                let [x, y, z] = dispatch_size(invocations);
+                pass.dispatch_workgroups(x, y, z);
+            }
+        });
+    }
+
+    items
+}
+
+/// Generates helpers which convert a number of invocations into a number of workgroups to dispatch, using the
+/// `workgroup_size` function of a compute entry point whose workgroup size depends on overrides. If `workgroup_size`
+/// is fallible, then so is `dispatch_size`.
+fn make_override_dispatch_helpers(fallible: bool, args: &ModuleToTokensConfig) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    let dispatch_size = quote::quote! {
+        [
+            invocations[0].div_ceil(workgroup_size[0]),
+            invocations[1].div_ceil(workgroup_size[1]),
+            invocations[2].div_ceil(workgroup_size[2]),
+        ]
+    };
+    if fallible {
+        items.push(syn::parse_quote! {
+            #[doc = "Gives the number of workgroups required to cover the given number of invocations in each dimension, rounding up, given the pipeline-overridable constant values used to create the pipeline. Gives `None` if evaluating the workgroup size overflows or divides by zero, and panics if any dimension of the workgroup size is zero, both of which WGSL treats as errors when creating a pipeline."]
+            pub fn dispatch_size(overrides: &overrides::Overrides, invocations: [u32; 3]) -> Option<[u32; 3]> {
+                let workgroup_size = workgroup_size(overrides)?;
+                Some(#dispatch_size)
+            }
+        });
+    } else {
+        items.push(syn::parse_quote! {
+            #[doc = "Gives the number of workgroups required to cover the given number of invocations in each dimension, rounding up, given the pipeline-overridable constant values used to create the pipeline. Panics if any dimension of the workgroup size is zero, which WGSL treats as an error when creating a pipeline."]
+            pub fn dispatch_size(overrides: &overrides::Overrides, invocations: [u32; 3]) -> [u32; 3] {
+                let workgroup_size = workgroup_size(overrides);
+                #dispatch_size
+            }
+        });
+    }
+
+    if args.gen_wgpu {
+        let dispatch_size = match fallible {
+            true => quote::quote! {
+                dispatch_size(overrides, invocations)
+                    .expect("the workgroup size overflowed or divided by zero, so no pipeline could have been created with these overrides")
+            },
+            false => quote::quote! { dispatch_size(overrides, invocations) },
+        };
+        items.push(syn::parse_quote! {
+            #[doc = "Dispatches enough workgroups on the given compute pass to cover the given number of invocations in each dimension, given the pipeline-overridable constant values used to create the pipeline set on the pass. Panics if the workgroup size can't be evaluated or has a zero dimension, as then no pipeline could have been created with these values."]
+            pub fn dispatch(pass: &mut wgpu::ComputePass<'_>, overrides: &overrides::Overrides, invocations: [u32; 3]) {
+                let [x, y, z] = #dispatch_size;
+                pass.dispatch_workgroups(x, y, z);
+            }
+        });
+    }
+
+    items
+}
+
+/// Builds a collection of entry points into a collection of Rust module definitions containing
+/// each of the entry points' properties, such as name and workgroup size. Sources of the entry points are only
+/// generated if the info from validating the module is given.
+pub fn make_entry_points(
+    module: &naga::Module,
+    info: Option<&naga::valid::ModuleInfo>,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    for entry_point in module.entry_points.iter() {
+        let entry_point_items = crate::collect_tokenstream(make_entry_point(
+            entry_point,
+            module,
+            info,
+            types,
+            args,
+            diagnostics,
+        ));
+
+        let entry_point_name_ident = syn::parse_str::<syn::Ident>(&entry_point.name);
+        let entry_point_name_ident = match entry_point_name_ident {
+            Ok(entry_point_name_ident) => entry_point_name_ident,
+            Err(_) => continue,
+        };
+
+        let doc = args.doc_comments.item_attributes(&entry_point.name, None);
+        items.push(syn::Item::Mod(syn::parse_quote! {
+            #doc
+            pub mod #entry_point_name_ident {
+                #[allow(unused)]
+                use super::*;
+
+                #entry_point_items
+            }
+        }))
+    }
+
+    items
+}
+
+/// Removes entry points which don't have the name given
+pub(crate) fn filter_entry_points(module: &mut naga::Module, retain_entry_point: String) {
+    let old_entry_points = std::mem::take(&mut module.entry_points);
+    let filtered_entry_point = old_entry_points
+        .into_iter()
+        .find(|ep| ep.name == retain_entry_point);
+
+    module.entry_points = match filtered_entry_point {
+        Some(filtered_entry_point) => vec![filtered_entry_point],
+        None => vec![],
+    };
+}
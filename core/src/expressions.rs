@@ -0,0 +1,663 @@
+use std::collections::HashMap;
+
+use crate::ModuleToTokensConfig;
+
+/// Gives the scalar type of a scalar expression in `module.global_expressions`, if it can be told without evaluating
+/// the expression.
+fn expression_scalar(
+    handle: naga::Handle<naga::Expression>,
+    module: &naga::Module,
+) -> Option<naga::Scalar> {
+    let type_scalar =
+        |ty: naga::Handle<naga::Type>| module.types.get_handle(ty).ok()?.inner.scalar();
+    match *module.global_expressions.try_get(handle).ok()? {
+        naga::Expression::Literal(lit) => Some(lit.scalar()),
+        naga::Expression::Constant(constant) => {
+            type_scalar(module.constants.try_get(constant).ok()?.ty)
+        }
+        naga::Expression::Override(override_handle) => {
+            type_scalar(module.overrides.try_get(override_handle).ok()?.ty)
+        }
+        naga::Expression::Unary { expr, .. } => expression_scalar(expr, module),
+        naga::Expression::Binary { op, left, .. } => match op {
+            naga::BinaryOperator::Equal
+            | naga::BinaryOperator::NotEqual
+            | naga::BinaryOperator::Less
+            | naga::BinaryOperator::LessEqual
+            | naga::BinaryOperator::Greater
+            | naga::BinaryOperator::GreaterEqual => Some(naga::Scalar::BOOL),
+            _ => expression_scalar(left, module),
+        },
+        naga::Expression::As {
+            kind,
+            convert: Some(width),
+            ..
+        } => Some(naga::Scalar { kind, width }),
+        _ => None,
+    }
+}
+
+/// Converts a scalar expression in `module.global_expressions` into an equivalent Rust expression, which is evaluated
+/// at runtime. This allows expressions which depend on pipeline-overridable constants to be computed by the user
+/// after they have chosen the values of their overrides. The tokens for each override referenced are given by
+/// `resolve_override`.
+///
+/// WGSL treats integer overflow, division by zero and shifting by at least the bit width as errors when creating a
+/// pipeline, so integer arithmetic is checked, with `on_error` being evaluated in place of the result if it fails.
+/// This is typically `return None`, or a `panic!` in a constant. Along with the expression, gives whether any such
+/// checked arithmetic was emitted, so whether `on_error` may be evaluated.
+///
+/// Returns `None` if any part of the expression can't be represented.
+pub(crate) fn scalar_expression_to_tokens(
+    handle: naga::Handle<naga::Expression>,
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+    resolve_override: &impl Fn(naga::Handle<naga::Override>) -> Option<proc_macro2::TokenStream>,
+    on_error: &proc_macro2::TokenStream,
+) -> Option<(proc_macro2::TokenStream, bool)> {
+    let recurse =
+        |handle| scalar_expression_to_tokens(handle, module, args, resolve_override, on_error);
+    let is_integer = |handle| {
+        expression_scalar(handle, module).is_some_and(|scalar| {
+            matches!(
+                scalar.kind,
+                naga::ScalarKind::Sint | naga::ScalarKind::Uint | naga::ScalarKind::AbstractInt
+            )
+        })
+    };
+    let checked =
+        |receiver: proc_macro2::TokenStream, method: &str, operands: proc_macro2::TokenStream| {
+            let method = syn::Ident::new(method, proc_macro2::Span::call_site());
+            quote::quote! {
+                match (#receiver).#method(#operands) {
+                    Some(value) => value,
+                    None => #on_error,
+                }
+            }
+        };
+
+    match module.global_expressions.try_get(handle).ok()? {
+        naga::Expression::Literal(lit) => Some((crate::constants::literal_to_tokens(lit), false)),
+        naga::Expression::Constant(constant) => {
+            let constant = module.constants.try_get(*constant).ok()?;
+            recurse(constant.init)
+        }
+        naga::Expression::Override(override_handle) => {
+            Some((resolve_override(*override_handle)?, false))
+        }
+        naga::Expression::Unary { op, expr } => {
+            let is_integer = is_integer(*expr);
+            let (expr, fallible) = recurse(*expr)?;
+            let expr = match op {
+                naga::UnaryOperator::Negate if is_integer => {
+                    quote::quote! { (#expr).wrapping_neg() }
+                }
+                naga::UnaryOperator::Negate => quote::quote! { (-#expr) },
+                naga::UnaryOperator::LogicalNot | naga::UnaryOperator::BitwiseNot => {
+                    quote::quote! { (!#expr) }
+                }
+            };
+            Some((expr, fallible))
+        }
+        naga::Expression::Binary { op, left, right } => {
+            let is_integer = is_integer(*left);
+            let (left, left_fallible) = recurse(*left)?;
+            let (right, right_fallible) = recurse(*right)?;
+            let fallible = left_fallible || right_fallible;
+            let checked_method = match op {
+                naga::BinaryOperator::Add => Some("checked_add"),
+                naga::BinaryOperator::Subtract => Some("checked_sub"),
+                naga::BinaryOperator::Multiply => Some("checked_mul"),
+                naga::BinaryOperator::Divide => Some("checked_div"),
+                naga::BinaryOperator::Modulo => Some("checked_rem"),
+                naga::BinaryOperator::ShiftLeft => Some("checked_shl"),
+                naga::BinaryOperator::ShiftRight => Some("checked_shr"),
+                _ => None,
+            };
+            if let (true, Some(method)) = (is_integer, checked_method) {
+                return Some((checked(left, method, right), true));
+            }
+            let expr = match op {
+                naga::BinaryOperator::Add => quote::quote! { (#left + #right) },
+                naga::BinaryOperator::Subtract => quote::quote! { (#left - #right) },
+                naga::BinaryOperator::Multiply => quote::quote! { (#left * #right) },
+                naga::BinaryOperator::Divide => quote::quote! { (#left / #right) },
+                naga::BinaryOperator::Modulo => quote::quote! { (#left % #right) },
+                naga::BinaryOperator::Equal => quote::quote! { (#left == #right) },
+                naga::BinaryOperator::NotEqual => quote::quote! { (#left != #right) },
+                naga::BinaryOperator::Less => quote::quote! { (#left < #right) },
+                naga::BinaryOperator::LessEqual => quote::quote! { (#left <= #right) },
+                naga::BinaryOperator::Greater => quote::quote! { (#left > #right) },
+                naga::BinaryOperator::GreaterEqual => quote::quote! { (#left >= #right) },
+                naga::BinaryOperator::And => quote::quote! { (#left & #right) },
+                naga::BinaryOperator::ExclusiveOr => quote::quote! { (#left ^ #right) },
+                naga::BinaryOperator::InclusiveOr => quote::quote! { (#left | #right) },
+                naga::BinaryOperator::LogicalAnd => quote::quote! { (#left && #right) },
+                naga::BinaryOperator::LogicalOr => quote::quote! { (#left || #right) },
+                naga::BinaryOperator::ShiftLeft => quote::quote! { (#left << #right) },
+                naga::BinaryOperator::ShiftRight => quote::quote! { (#left >> #right) },
+            };
+            Some((expr, fallible))
+        }
+        // Rust can't cast to `bool` with `as`, so we only support numeric conversions here.
+        naga::Expression::As {
+            expr,
+            kind,
+            convert: Some(width),
+        } if *kind != naga::ScalarKind::Bool => {
+            let (expr, fallible) = recurse(*expr)?;
+            let ty = crate::types::rust_type(
+                &naga::TypeInner::Scalar(naga::Scalar {
+                    kind: *kind,
+                    width: *width,
+                }),
+                args,
+            )?;
+            Some((quote::quote! { (#expr as #ty) }, fallible))
+        }
+        _ => None,
+    }
+}
+
+/// The value of a constant expression, after evaluation.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ConstValue {
+    /// A single scalar value.
+    Scalar(naga::Literal),
+    /// The components of a vector, the columns of a matrix, the elements of an array or the members of a struct.
+    Composite(Vec<ConstValue>),
+}
+
+impl ConstValue {
+    /// Constructs the zero value of a type.
+    fn zero(ty: naga::Handle<naga::Type>, module: &naga::Module) -> Option<Self> {
+        match &module.types.get_handle(ty).ok()?.inner {
+            naga::TypeInner::Scalar(scalar) | naga::TypeInner::Atomic(scalar) => {
+                naga::Literal::zero(*scalar).map(ConstValue::Scalar)
+            }
+            naga::TypeInner::Vector { size, scalar } => {
+                let zero = ConstValue::Scalar(naga::Literal::zero(*scalar)?);
+                Some(ConstValue::Composite(vec![zero; *size as usize]))
+            }
+            naga::TypeInner::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => {
+                let zero = ConstValue::Scalar(naga::Literal::zero(*scalar)?);
+                let column = ConstValue::Composite(vec![zero; *rows as usize]);
+                Some(ConstValue::Composite(vec![column; *columns as usize]))
+            }
+            naga::TypeInner::Array {
+                base,
+                size: naga::ArraySize::Constant(size),
+                ..
+            } => {
+                let zero = ConstValue::zero(*base, module)?;
+                Some(ConstValue::Composite(vec![zero; size.get() as usize]))
+            }
+            naga::TypeInner::Struct { members, .. } => members
+                .iter()
+                .map(|member| ConstValue::zero(member.ty, module))
+                .collect::<Option<Vec<_>>>()
+                .map(ConstValue::Composite),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the value of an expression in `module.global_expressions` which has already been evaluated, which naga
+/// represents as a tree of literals, zero values, splats and composes.
+fn evaluated_value(
+    handle: naga::Handle<naga::Expression>,
+    module: &naga::Module,
+) -> Option<ConstValue> {
+    match module.global_expressions.try_get(handle).ok()? {
+        naga::Expression::Literal(lit) => Some(ConstValue::Scalar(*lit)),
+        naga::Expression::Constant(constant) => {
+            evaluated_value(module.constants.try_get(*constant).ok()?.init, module)
+        }
+        naga::Expression::ZeroValue(ty) => ConstValue::zero(*ty, module),
+        naga::Expression::Compose { ty, components } => {
+            let components = components
+                .iter()
+                .map(|component| evaluated_value(*component, module))
+                .collect::<Option<Vec<_>>>()?;
+            match &module.types.get_handle(*ty).ok()?.inner {
+                // Vectors may be built out of smaller vectors, so flatten these
+                naga::TypeInner::Vector { .. } => Some(ConstValue::Composite(
+                    components
+                        .into_iter()
+                        .flat_map(|component| match component {
+                            ConstValue::Scalar(_) => vec![component],
+                            ConstValue::Composite(components) => components,
+                        })
+                        .collect(),
+                )),
+                _ => Some(ConstValue::Composite(components)),
+            }
+        }
+        naga::Expression::Splat { size, value } => match evaluated_value(*value, module)? {
+            value @ ConstValue::Scalar(_) => {
+                Some(ConstValue::Composite(vec![value; *size as usize]))
+            }
+            ConstValue::Composite(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Rebuilds an expression in `module.global_expressions` through naga's constant evaluator, replacing each override
+/// with its default value. Returns the handle of the evaluated expression, or `None` if naga can't evaluate it.
+fn fold(
+    handle: naga::Handle<naga::Expression>,
+    module: &mut naga::Module,
+    tracker: &mut naga::proc::ExpressionKindTracker,
+    folded: &mut HashMap<naga::Handle<naga::Expression>, naga::Handle<naga::Expression>>,
+) -> Option<naga::Handle<naga::Expression>> {
+    if let Some(&handle) = folded.get(&handle) {
+        return Some(handle);
+    }
+
+    let expr = module.global_expressions.try_get(handle).ok()?.clone();
+    let init = match expr {
+        naga::Expression::Constant(constant) => Some(module.constants.try_get(constant).ok()?.init),
+        naga::Expression::Override(override_handle) => {
+            Some(module.overrides.try_get(override_handle).ok()?.init?)
+        }
+        _ => None,
+    };
+
+    let mut operand = |handle| fold(handle, module, tracker, folded);
+    if let Some(init) = init {
+        return operand(init);
+    }
+    let expr = match expr {
+        naga::Expression::Literal(_) | naga::Expression::ZeroValue(_) => return Some(handle),
+        naga::Expression::Compose { ty, components } => naga::Expression::Compose {
+            ty,
+            components: components
+                .into_iter()
+                .map(&mut operand)
+                .collect::<Option<_>>()?,
+        },
+        naga::Expression::Splat { size, value } => naga::Expression::Splat {
+            size,
+            value: operand(value)?,
+        },
+        naga::Expression::Access { base, index } => naga::Expression::Access {
+            base: operand(base)?,
+            index: operand(index)?,
+        },
+        naga::Expression::AccessIndex { base, index } => naga::Expression::AccessIndex {
+            base: operand(base)?,
+            index,
+        },
+        naga::Expression::Swizzle {
+            size,
+            vector,
+            pattern,
+        } => naga::Expression::Swizzle {
+            size,
+            vector: operand(vector)?,
+            pattern,
+        },
+        naga::Expression::Unary { op, expr } => naga::Expression::Unary {
+            op,
+            expr: operand(expr)?,
+        },
+        naga::Expression::Binary { op, left, right } => naga::Expression::Binary {
+            op,
+            left: operand(left)?,
+            right: operand(right)?,
+        },
+        naga::Expression::As {
+            expr,
+            kind,
+            convert,
+        } => naga::Expression::As {
+            expr: operand(expr)?,
+            kind,
+            convert,
+        },
+        naga::Expression::Math {
+            fun,
+            arg,
+            arg1,
+            arg2,
+            arg3,
+        } => naga::Expression::Math {
+            fun,
+            arg: operand(arg)?,
+            arg1: match arg1 {
+                Some(arg1) => Some(operand(arg1)?),
+                None => None,
+            },
+            arg2: match arg2 {
+                Some(arg2) => Some(operand(arg2)?),
+                None => None,
+            },
+            arg3: match arg3 {
+                Some(arg3) => Some(operand(arg3)?),
+                None => None,
+            },
+        },
+        _ => return None,
+    };
+
+    let span = module.global_expressions.get_span(handle);
+    let evaluated = naga::proc::ConstantEvaluator::for_wgsl_module(module, tracker, false)
+        .try_eval_and_append(expr, span)
+        .ok()?;
+    folded.insert(handle, evaluated);
+    Some(evaluated)
+}
+
+/// Evaluates an expression in `module.global_expressions` to a value. Naga's front ends evaluate the initializers of
+/// constants as they parse them, so these are read directly. Anything else, such as an expression which depends on
+/// overrides, is evaluated on a copy of the module by naga's constant evaluator, with overrides taking their default
+/// values.
+pub(crate) fn evaluate(
+    handle: naga::Handle<naga::Expression>,
+    module: &naga::Module,
+) -> Option<ConstValue> {
+    if let Some(value) = evaluated_value(handle, module) {
+        return Some(value);
+    }
+
+    let mut module = module.clone();
+    let mut tracker = naga::proc::ExpressionKindTracker::from_arena(&module.global_expressions);
+    let handle = fold(handle, &mut module, &mut tracker, &mut HashMap::new())?;
+    evaluated_value(handle, &module)
+}
+
+/// Converts a literal to the given concrete scalar type, as happens when an abstract value is assigned to a typed
+/// constant. Returns `None` if the conversion isn't allowed, or if the value doesn't fit in the type.
+pub(crate) fn concretize_literal(
+    lit: naga::Literal,
+    scalar: naga::Scalar,
+) -> Option<naga::Literal> {
+    if lit.scalar() == scalar {
+        return Some(lit);
+    }
+
+    match (lit, scalar.kind, scalar.width) {
+        (naga::Literal::AbstractInt(v), naga::ScalarKind::Uint, 4) => {
+            v.try_into().ok().map(naga::Literal::U32)
+        }
+        (naga::Literal::AbstractInt(v), naga::ScalarKind::Uint, 8) => {
+            v.try_into().ok().map(naga::Literal::U64)
+        }
+        (naga::Literal::AbstractInt(v), naga::ScalarKind::Sint, 4) => {
+            v.try_into().ok().map(naga::Literal::I32)
+        }
+        (naga::Literal::AbstractInt(v), naga::ScalarKind::Sint, 8) => Some(naga::Literal::I64(v)),
+        (naga::Literal::AbstractInt(v), naga::ScalarKind::Float, 4) => {
+            Some(naga::Literal::F32(v as f32))
+        }
+        (naga::Literal::AbstractInt(v), naga::ScalarKind::Float, 8) => {
+            Some(naga::Literal::F64(v as f64))
+        }
+        (naga::Literal::AbstractFloat(v), naga::ScalarKind::Float, 4) => {
+            let v = v as f32;
+            v.is_finite().then_some(naga::Literal::F32(v))
+        }
+        (naga::Literal::AbstractFloat(v), naga::ScalarKind::Float, 8) => {
+            Some(naga::Literal::F64(v))
+        }
+        _ => None,
+    }
+}
+
+/// Formats a literal as it would be written in WGSL, for use in diagnostics.
+pub(crate) fn literal_to_string(lit: naga::Literal) -> String {
+    match lit {
+        naga::Literal::F64(v) => format!("{v}lf"),
+        naga::Literal::F32(v) => format!("{v}f"),
+        naga::Literal::U32(v) => format!("{v}u"),
+        naga::Literal::I32(v) => format!("{v}i"),
+        naga::Literal::U64(v) => format!("{v}lu"),
+        naga::Literal::I64(v) => format!("{v}li"),
+        naga::Literal::Bool(v) => v.to_string(),
+        naga::Literal::AbstractInt(v) => v.to_string(),
+        naga::Literal::AbstractFloat(v) => format!("{v:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn append(module: &mut naga::Module, expr: naga::Expression) -> naga::Handle<naga::Expression> {
+        module
+            .global_expressions
+            .append(expr, naga::Span::UNDEFINED)
+    }
+
+    fn literal(module: &mut naga::Module, lit: naga::Literal) -> naga::Handle<naga::Expression> {
+        append(module, naga::Expression::Literal(lit))
+    }
+
+    /// Evaluates a binary operation on two scalars.
+    fn binary(
+        op: naga::BinaryOperator,
+        left: naga::Literal,
+        right: naga::Literal,
+    ) -> Option<ConstValue> {
+        let mut module = naga::Module::default();
+        let left = literal(&mut module, left);
+        let right = literal(&mut module, right);
+        let handle = append(&mut module, naga::Expression::Binary { op, left, right });
+        evaluate(handle, &module)
+    }
+
+    /// Evaluates a unary operation on a scalar.
+    fn unary(op: naga::UnaryOperator, value: naga::Literal) -> Option<ConstValue> {
+        let mut module = naga::Module::default();
+        let expr = literal(&mut module, value);
+        let handle = append(&mut module, naga::Expression::Unary { op, expr });
+        evaluate(handle, &module)
+    }
+
+    /// Evaluates a value conversion of a scalar to a 32-bit scalar of the given kind.
+    fn convert(value: naga::Literal, kind: naga::ScalarKind) -> Option<ConstValue> {
+        let mut module = naga::Module::default();
+        let expr = literal(&mut module, value);
+        let handle = append(
+            &mut module,
+            naga::Expression::As {
+                expr,
+                kind,
+                convert: Some(4),
+            },
+        );
+        evaluate(handle, &module)
+    }
+
+    fn scalar(lit: naga::Literal) -> Option<ConstValue> {
+        Some(ConstValue::Scalar(lit))
+    }
+
+    #[test]
+    fn shifts_by_bit_width_or_more_fail() {
+        use naga::BinaryOperator::{ShiftLeft, ShiftRight};
+        use naga::Literal::{I32, U32};
+
+        assert_eq!(binary(ShiftLeft, U32(1), U32(31)), scalar(U32(1 << 31)));
+        assert_eq!(binary(ShiftLeft, U32(0), U32(32)), None);
+        assert_eq!(binary(ShiftRight, U32(1), U32(32)), None);
+        assert_eq!(binary(ShiftRight, I32(-8), U32(1)), scalar(I32(-4)));
+        assert_eq!(binary(ShiftRight, I32(-8), U32(32)), None);
+    }
+
+    #[test]
+    fn bitwise_not_depends_on_signedness() {
+        use naga::Literal::{I32, U32};
+        use naga::UnaryOperator::BitwiseNot;
+
+        assert_eq!(unary(BitwiseNot, U32(0)), scalar(U32(u32::MAX)));
+        assert_eq!(unary(BitwiseNot, U32(0xF0)), scalar(U32(!0xF0)));
+        assert_eq!(unary(BitwiseNot, I32(0)), scalar(I32(-1)));
+        assert_eq!(unary(BitwiseNot, I32(i32::MIN)), scalar(I32(i32::MAX)));
+    }
+
+    #[test]
+    fn i32_arithmetic_wraps_as_naga_does() {
+        use naga::BinaryOperator::{Add, Divide, Modulo, Multiply, Subtract};
+        use naga::Literal::I32;
+
+        // Naga's front ends fold constants with wrapping arithmetic, so evaluation must agree with them
+        assert_eq!(binary(Add, I32(i32::MAX), I32(1)), scalar(I32(i32::MIN)));
+        assert_eq!(
+            binary(Subtract, I32(i32::MIN), I32(1)),
+            scalar(I32(i32::MAX))
+        );
+        assert_eq!(binary(Multiply, I32(1 << 16), I32(1 << 16)), scalar(I32(0)));
+        assert_eq!(
+            unary(naga::UnaryOperator::Negate, I32(i32::MIN)),
+            scalar(I32(i32::MIN))
+        );
+        assert_eq!(binary(Divide, I32(1), I32(0)), None);
+        assert_eq!(binary(Modulo, I32(1), I32(0)), None);
+    }
+
+    #[test]
+    fn float_to_int_conversions_saturate() {
+        use naga::Literal::{F32, I32, U32};
+
+        assert_eq!(
+            convert(F32(1e20), naga::ScalarKind::Sint),
+            scalar(I32(i32::MAX))
+        );
+        assert_eq!(
+            convert(F32(-1e20), naga::ScalarKind::Sint),
+            scalar(I32(i32::MIN))
+        );
+        assert_eq!(convert(F32(-5.5), naga::ScalarKind::Uint), scalar(U32(0)));
+        assert_eq!(
+            convert(F32(1e10), naga::ScalarKind::Uint),
+            scalar(U32(u32::MAX))
+        );
+        assert_eq!(convert(F32(-2.75), naga::ScalarKind::Sint), scalar(I32(-2)));
+    }
+
+    #[test]
+    fn same_width_integer_conversions_reinterpret() {
+        use naga::Literal::{I32, U32};
+
+        assert_eq!(
+            convert(I32(-1), naga::ScalarKind::Uint),
+            scalar(U32(u32::MAX))
+        );
+        assert_eq!(
+            convert(U32(0xFFFF_FFFF), naga::ScalarKind::Sint),
+            scalar(I32(-1))
+        );
+        assert_eq!(convert(I32(7), naga::ScalarKind::Uint), scalar(U32(7)));
+    }
+
+    #[test]
+    fn overrides_take_their_default_values() {
+        let mut module = naga::Module::default();
+        let ty = module.types.insert(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+            },
+            naga::Span::UNDEFINED,
+        );
+        let default = literal(&mut module, naga::Literal::U32(3));
+        let with_default = module.overrides.append(
+            naga::Override {
+                name: Some("with_default".into()),
+                id: None,
+                ty,
+                init: Some(default),
+            },
+            naga::Span::UNDEFINED,
+        );
+        let without_default = module.overrides.append(
+            naga::Override {
+                name: Some("without_default".into()),
+                id: None,
+                ty,
+                init: None,
+            },
+            naga::Span::UNDEFINED,
+        );
+        let mut doubled = |override_handle| {
+            let left = append(&mut module, naga::Expression::Override(override_handle));
+            let right = literal(&mut module, naga::Literal::U32(2));
+            append(
+                &mut module,
+                naga::Expression::Binary {
+                    op: naga::BinaryOperator::Multiply,
+                    left,
+                    right,
+                },
+            )
+        };
+        let with_default = doubled(with_default);
+        let without_default = doubled(without_default);
+
+        assert_eq!(
+            evaluate(with_default, &module),
+            scalar(naga::Literal::U32(6))
+        );
+        assert_eq!(evaluate(without_default, &module), None);
+    }
+
+    #[test]
+    fn access_into_composes() {
+        let mut module = naga::Module::default();
+        let ty = module.types.insert(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Vector {
+                    size: naga::VectorSize::Tri,
+                    scalar: naga::Scalar::F32,
+                },
+            },
+            naga::Span::UNDEFINED,
+        );
+        let components = [1.0, 2.0, 3.0]
+            .into_iter()
+            .map(|v| literal(&mut module, naga::Literal::F32(v)))
+            .collect();
+        let v = append(&mut module, naga::Expression::Compose { ty, components });
+        let index = literal(&mut module, naga::Literal::U32(1));
+        let out_of_bounds = literal(&mut module, naga::Literal::U32(3));
+        let access = append(&mut module, naga::Expression::Access { base: v, index });
+        let access_out_of_bounds = append(
+            &mut module,
+            naga::Expression::Access {
+                base: v,
+                index: out_of_bounds,
+            },
+        );
+        let access_index = append(
+            &mut module,
+            naga::Expression::AccessIndex { base: v, index: 2 },
+        );
+        let access_index_out_of_bounds = append(
+            &mut module,
+            naga::Expression::AccessIndex { base: v, index: 3 },
+        );
+
+        assert_eq!(
+            evaluate(v, &module),
+            Some(ConstValue::Composite(vec![
+                ConstValue::Scalar(naga::Literal::F32(1.0)),
+                ConstValue::Scalar(naga::Literal::F32(2.0)),
+                ConstValue::Scalar(naga::Literal::F32(3.0)),
+            ]))
+        );
+        assert_eq!(evaluate(access, &module), scalar(naga::Literal::F32(2.0)));
+        assert_eq!(
+            evaluate(access_index, &module),
+            scalar(naga::Literal::F32(3.0))
+        );
+        assert_eq!(evaluate(access_out_of_bounds, &module), None);
+        assert_eq!(evaluate(access_index_out_of_bounds, &module), None);
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(any(feature = "glsl-in", feature = "spv-in"))]
+use std::path::Path;
+
+/// Parses a GLSL shader for the given stage into a module, with the given preprocessor definitions, each akin to
+/// `#define key value` at the top of the shader.
+#[cfg(feature = "glsl-in")]
+pub fn parse_glsl(
+    source: &str,
+    stage: naga::ShaderStage,
+    defines: impl IntoIterator<Item = (String, String)>,
+) -> Result<naga::Module, naga::front::glsl::ParseErrors> {
+    let mut options = naga::front::glsl::Options::from(stage);
+    options.defines.extend(defines);
+    naga::front::glsl::Frontend::default().parse(&options, source)
+}
+
+/// Gives the stage of a GLSL shader from the extension of its path, if it uses one of the conventional `.vert`,
+/// `.frag` or `.comp` extensions.
+#[cfg(feature = "glsl-in")]
+pub fn glsl_stage_from_path(path: &Path) -> Option<naga::ShaderStage> {
+    match path.extension()?.to_str()? {
+        "vert" => Some(naga::ShaderStage::Vertex),
+        "frag" => Some(naga::ShaderStage::Fragment),
+        "comp" => Some(naga::ShaderStage::Compute),
+        _ => None,
+    }
+}
+
+/// Parses a SPIR-V binary into a module.
+#[cfg(feature = "spv-in")]
+pub fn parse_spirv(data: &[u8]) -> Result<naga::Module, naga::front::spv::Error> {
+    naga::front::spv::parse_u8_slice(data, &naga::front::spv::Options::default())
+}
+
+/// Whether a path has the conventional `.spv` extension of a SPIR-V binary.
+#[cfg(feature = "spv-in")]
+pub fn is_spirv_path(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "spv")
+}
@@ -94,7 +94,7 @@ pub fn make_globals(
     let mut globals = Vec::new();
 
     // Info about each global individually
-    for (_, global) in module.global_variables.iter() {
+    for (global_handle, global) in module.global_variables.iter() {
         // Get name for global module
         let global_name = match &global.name {
             Some(name) => name.clone(),
@@ -111,11 +111,13 @@ pub fn make_globals(
 
         // Collate into an inner module
         let doc = format!(
-            "Information about the `{}` global variable within this shader module.",
-            global_name
+            "Information about the `{}` global variable within this shader module.{}",
+            global_name,
+            crate::declared_at(module.global_variables.get_span(global_handle), args)
         );
+        let doc = args.doc_comments.item_attributes(&global_name, Some(&doc));
         globals.push(syn::parse_quote! {
-            #[doc = #doc]
+            #doc
             pub mod #global_name_ident {
                 #[allow(unused)]
                 use super::*;
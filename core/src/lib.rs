@@ -0,0 +1,597 @@
+#![deny(missing_docs)]
+//! The implementation of `naga-to-tokenstream`. Depend on `naga-to-tokenstream` rather than on this crate directly,
+//! as it re-exports everything here along with its procedural macros.
+
+use std::collections::{HashMap, HashSet};
+
+mod backends;
+/// Helpers for generating Rust definitions for shaders from build scripts.
+pub mod build;
+mod capabilities;
+/// Methods for converting sets of `naga::Constant`s to token streams.
+pub mod constants;
+/// Warnings and errors produced while converting a module to a token stream.
+pub mod diagnostics;
+/// Doc comments written in shader source, for carrying into the generated Rust documentation.
+pub mod docs;
+/// Methods for converting sets of `naga::EntryPoint`s to token streams.
+pub mod entry_points;
+mod expressions;
+/// Helpers for parsing shaders written in languages other than WGSL, enabled by the `glsl-in` and `spv-in`
+/// features.
+pub mod front;
+/// Methods for converting sets of `naga::GlobalVariable`s to token streams.
+pub mod globals;
+#[cfg(feature = "minify")]
+mod minify;
+/// Methods for converting sets of `naga::Override`s to token streams.
+pub mod overrides;
+mod reachability;
+/// Methods for converting sets of `naga::Type`s to token streams.
+pub mod types;
+
+fn collect_tokenstream<I: quote::ToTokens>(
+    items: impl IntoIterator<Item = I>,
+) -> proc_macro2::TokenStream {
+    let mut tokens = proc_macro2::TokenStream::new();
+    for item in items {
+        item.to_tokens(&mut tokens);
+    }
+
+    tokens
+}
+
+/// Renders a validation error against the module's original source code, if it was given, along with the location
+/// of the first span of the error.
+fn render_validation_error(
+    error: &naga::WithSpan<naga::valid::ValidationError>,
+    args: &ModuleToTokensConfig,
+) -> (String, Option<diagnostics::Location>) {
+    let rendered = match &args.source {
+        Some(source) => match &source.path {
+            Some(path) => error.emit_to_string_with_path(&source.code, &path.to_string_lossy()),
+            None => error.emit_to_string(&source.code),
+        },
+        None => error.as_inner().to_string(),
+    };
+    // The rendered error has its own `error: ` heading, which diagnostics give instead
+    let rendered = rendered.trim_end();
+    let rendered = rendered.strip_prefix("error: ").unwrap_or(rendered);
+    let location = error
+        .spans()
+        .find_map(|&(span, _)| source_location(span, args));
+    (rendered.to_owned(), location)
+}
+
+/// Validates the module with the flags and capabilities given in the configuration. If validation fails, an error is
+/// recorded, since none of the sources of the shader can then be generated.
+fn validate_module(
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut diagnostics::Diagnostics,
+) -> Option<naga::valid::ModuleInfo> {
+    match naga::valid::Validator::new(args.validation.flags, args.validation.capabilities)
+        .validate(module)
+    {
+        Ok(info) => Some(info),
+        Err(error) => {
+            let start = diagnostics.len();
+            let (rendered, location) = render_validation_error(&error, args);
+            diagnostics.error(format!(
+                "the module failed validation, so no sources were generated: {}",
+                rendered
+            ));
+            diagnostics.locate_since(start, location);
+            None
+        }
+    }
+}
+
+/// Gives the info for a module derived from the validated module, such as by removing entry points or compacting
+/// its arenas. `ModuleInfo` is indexed by handles and entry point, so can't be reused for a derived module, but since
+/// the original module has already been validated, only a pass with no validation flags is needed to rebuild it.
+fn derived_module_info(
+    module: &naga::Module,
+    describe: &str,
+    generated: &str,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut diagnostics::Diagnostics,
+) -> Option<naga::valid::ModuleInfo> {
+    match naga::valid::Validator::new(
+        naga::valid::ValidationFlags::empty(),
+        args.validation.capabilities,
+    )
+    .validate(module)
+    {
+        Ok(info) => Some(info),
+        Err(error) => {
+            let start = diagnostics.len();
+            let (rendered, location) = render_validation_error(&error, args);
+            diagnostics.warn(format!(
+                "{} failed validation, so no {} generated: {}",
+                describe, generated, rendered
+            ));
+            diagnostics.locate_since(start, location);
+            None
+        }
+    }
+}
+
+/// Writes a validated module out as WGSL, where `describe` describes the module for use in diagnostics. If the module
+/// can't be written, a warning is recorded explaining why the `constant` holding the source won't be generated.
+#[cfg_attr(not(feature = "minify"), allow(unused_variables))]
+fn module_to_source(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    describe: &str,
+    constant: &str,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut diagnostics::Diagnostics,
+) -> Option<String> {
+    // If we minify fully, do the first pass before writing out, which changes the module so its info must be rebuilt
+    #[cfg(feature = "minify")]
+    let minified;
+    #[cfg(feature = "minify")]
+    let (module, info) = if args.minify == MinifyLevel::Full {
+        let mut module = module.clone();
+        minify::minify_module(&mut module);
+        let info = derived_module_info(
+            &module,
+            describe,
+            &format!("`{}` was", constant),
+            args,
+            diagnostics,
+        )?;
+        minified = (module, info);
+        (&minified.0, &minified.1)
+    } else {
+        (module, info)
+    };
+
+    // Write to wgsl
+    let src = match naga::back::wgsl::write_string(
+        module,
+        info,
+        naga::back::wgsl::WriterFlags::empty(),
+    ) {
+        Ok(src) => src,
+        Err(error) => {
+            diagnostics.warn(format!(
+                "failed to write {} as WGSL, so no `{}` was generated: {}",
+                describe, constant, error
+            ));
+            return None;
+        }
+    };
+
+    // Remove whitespace if minifying
+    #[cfg(feature = "minify")]
+    let src = match args.minify {
+        MinifyLevel::None => src,
+        MinifyLevel::Whitespace | MinifyLevel::Full => wgsl_minifier::minify_wgsl_source(&src),
+    };
+
+    Some(src)
+}
+
+/// Gives the location of a span within the module's original source code, if the source was given and the span is
+/// known.
+fn source_location(span: naga::Span, args: &ModuleToTokensConfig) -> Option<diagnostics::Location> {
+    args.source.as_ref()?.location(span)
+}
+
+/// Gives a sentence describing where an item was declared in the module's original source code, with a leading
+/// space so that it can be appended to other documentation, or an empty string if the location isn't known.
+fn declared_at(span: naga::Span, args: &ModuleToTokensConfig) -> String {
+    match source_location(span, args) {
+        Some(location) => format!(" Declared at `{}`.", location),
+        None => String::new(),
+    }
+}
+
+/// Replaces the comments in WGSL source code with spaces, so that commented-out code isn't mistaken for real code,
+/// keeping line breaks so that lines still line up with the original. Block comments may be nested. Line comments,
+/// including doc comments, are kept if `keep_line_comments` is set, although block comments are still recognised
+/// within them.
+fn strip_comments(code: &str, keep_line_comments: bool) -> String {
+    let mut stripped = String::with_capacity(code.len());
+    let mut chars = code.chars().peekable();
+    let mut block_depth = 0usize;
+    let mut in_line_comment = false;
+    while let Some(c) = chars.next() {
+        let next = chars.peek().copied();
+        if in_line_comment {
+            if matches!(c, '\n' | '\r') {
+                in_line_comment = false;
+                stripped.push(c);
+            } else {
+                stripped.push(if keep_line_comments { c } else { ' ' });
+            }
+        } else if c == '/' && next == Some('*') {
+            chars.next();
+            block_depth += 1;
+            stripped.push_str("  ");
+        } else if block_depth > 0 && c == '*' && next == Some('/') {
+            chars.next();
+            block_depth -= 1;
+            stripped.push_str("  ");
+        } else if block_depth > 0 {
+            stripped.push(if c == '\n' { '\n' } else { ' ' });
+        } else if c == '/' && next == Some('/') {
+            chars.next();
+            in_line_comment = true;
+            stripped.push_str(if keep_line_comments { "//" } else { "  " });
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+/// The original source code that a module was parsed from.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleSource {
+    /// The source code, exactly as it was given to the parser.
+    pub code: String,
+    /// The path of the file that the source code was read from, if any, used when describing locations.
+    pub path: Option<std::path::PathBuf>,
+    /// Embed `code` verbatim as the `SOURCE` constant, rather than source code written out from the parsed module.
+    pub embed_verbatim: bool,
+}
+
+impl ModuleSource {
+    /// Gives the location within the source code of a span from the parsed module, if the span is known.
+    pub fn location(&self, span: naga::Span) -> Option<diagnostics::Location> {
+        let range = span.to_range()?;
+        // Spans from some other source code can't be located
+        self.code.get(range)?;
+        let location = span.location(&self.code);
+        Some(diagnostics::Location {
+            path: self.path.clone(),
+            line: location.line_number,
+            column: location.line_position,
+        })
+    }
+
+    /// Whether the source code is WGSL, judged by its path having the `.wgsl` extension or none at all.
+    pub fn is_wgsl(&self) -> bool {
+        self.path
+            .as_ref()
+            .and_then(|path| path.extension())
+            .is_none_or(|extension| extension == "wgsl")
+    }
+}
+
+/// Options used when compiling the module and its entry points to SPIR-V, when `gen_spirv` is enabled.
+#[derive(Debug, Clone)]
+pub struct SpirvOptions {
+    /// The version of SPIR-V to target, as `(major, minor)`. Defaults to 1.0.
+    pub lang_version: (u8, u8),
+    /// Include debug information, such as the names of variables and functions. If the module's `source` is given,
+    /// the source code is also embedded along with the locations of instructions within it.
+    pub debug_info: bool,
+    /// How out-of-bounds indexing is handled by the compiled shader.
+    pub bounds_check_policies: naga::proc::BoundsCheckPolicies,
+}
+
+impl Default for SpirvOptions {
+    fn default() -> Self {
+        Self {
+            lang_version: (1, 0),
+            debug_info: false,
+            bounds_check_policies: naga::proc::BoundsCheckPolicies::default(),
+        }
+    }
+}
+
+/// Options used when validating the module, before any sources of the shader are generated.
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// The validation checks to perform. Defaults to all of them.
+    pub flags: naga::valid::ValidationFlags,
+    /// The capabilities supported by the target, which the module may use. Defaults to all of them, so should be
+    /// narrowed to learn when a shader needs a capability that the target lacks.
+    pub capabilities: naga::valid::Capabilities,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            flags: naga::valid::ValidationFlags::all(),
+            capabilities: naga::valid::Capabilities::all(),
+        }
+    }
+}
+
+/// How much the WGSL sources of the shader, `SOURCE` and `EXCLUSIVE_SOURCE`, are minified. Any level other than
+/// `None` requires the `minify` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinifyLevel {
+    /// The sources are written out in full.
+    None,
+    /// Whitespace, trailing commas and redundant parentheses are removed, leaving every identifier unchanged.
+    Whitespace,
+    /// Along with removing whitespace, unused items are removed and identifiers are shortened. The names of entry
+    /// points, overrides and globals with bindings are kept, so they can still be looked up by the generated `NAME`
+    /// constants.
+    Full,
+}
+
+impl Default for MinifyLevel {
+    /// Minifies fully if the `minify` feature is enabled, and not at all otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "minify") {
+            Self::Full
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// The configuration required to create a token stream describing a module.
+#[derive(Default, Clone)]
+pub struct ModuleToTokensConfig {
+    /// A filter on the structs to expose. This is useful specifically when using the `encase` feature,
+    /// since many structs can't be encoded or decoded. It is therefore the using crate's responsibility
+    /// to expose this in some way, for example by having structs that should be exported to Rust require
+    /// an attribute.
+    pub structs_filter: Option<HashSet<String>>,
+    /// Generate `glam` types.
+    pub gen_glam: bool,
+    /// Generate `encase` types.
+    pub gen_encase: bool,
+    /// Generate `naga` types.
+    pub gen_naga: bool,
+    /// Generate `wgpu` helpers.
+    pub gen_wgpu: bool,
+    /// Generate `Default` implementations and `const fn zeroed()` constructors for structs, matching WGSL's
+    /// zero values.
+    pub gen_default: bool,
+    /// Derive `serde::Serialize` and `serde::Deserialize` on generated structs and the `Overrides` struct. If
+    /// `glam` is enabled, then `glam`'s `serde` feature must also be enabled. Structs containing arrays which
+    /// `serde` can't handle, such as those longer than 32 elements, don't derive these.
+    pub gen_serde: bool,
+    /// Embed the module and each entry point compiled to SPIR-V, as `SPIRV` constants. Requires the `spv-out`
+    /// feature. If `wgpu` helpers are also generated, a `spirv_shader_module_descriptor` function is generated
+    /// alongside each constant, allowing the shader to be loaded without parsing WGSL at runtime.
+    pub gen_spirv: bool,
+    /// Embed each entry point translated to GLSL, as `GLSL` constants. Requires the `glsl-out` feature.
+    pub gen_glsl: bool,
+    /// Embed the module and each entry point translated to HLSL, as `HLSL` constants. Requires the `hlsl-out`
+    /// feature.
+    pub gen_hlsl: bool,
+    /// Embed the module and each entry point translated to the Metal Shading Language, as `MSL` constants. Requires
+    /// the `msl-out` feature.
+    pub gen_msl: bool,
+    /// The options used when compiling to SPIR-V, when `gen_spirv` is enabled.
+    pub spirv_options: SpirvOptions,
+    /// The options used to validate the module before generating `SOURCE`, `EXCLUSIVE_SOURCE` and sources in other
+    /// shader languages. If validation fails, an error is reported and none of these are generated.
+    pub validation: ValidationOptions,
+    /// How much `SOURCE` and `EXCLUSIVE_SOURCE` are minified. Defaults to `MinifyLevel::Full` if the `minify`
+    /// feature is enabled, and `MinifyLevel::None` otherwise.
+    pub minify: MinifyLevel,
+    /// Extra derive macros to apply to every generated struct, for example `serde::Serialize` or `Copy`. Standard
+    /// traits which can't be derived for a struct, such as `Copy` on a struct containing a runtime-sized array,
+    /// are skipped for that struct.
+    pub struct_derives: Vec<syn::Path>,
+    /// Extra derive macros to apply to specific generated structs, keyed by the name of the struct in the shader.
+    /// These are skipped where they can't hold in the same way as `struct_derives`.
+    pub struct_derives_by_name: HashMap<String, Vec<syn::Path>>,
+    /// Extra attributes to apply to every generated struct, for example `#[cfg_attr(...)]` or `#[repr(C)]`.
+    pub struct_attributes: Vec<syn::Attribute>,
+    /// Extra attributes to apply to specific generated structs, keyed by the name of the struct in the shader.
+    pub struct_attributes_by_name: HashMap<String, Vec<syn::Attribute>>,
+    /// The doc comments written in the shader source, which are attached to the corresponding generated structs,
+    /// struct members, constants, overrides, globals and entry points. These can be read from WGSL source with
+    /// `DocComments::from_wgsl`.
+    pub doc_comments: docs::DocComments,
+    /// The source code that the module was parsed from. If given, generated items are documented with where they
+    /// were declared, diagnostics point at the relevant source, and the doc comments are read from the source when
+    /// `doc_comments` is left empty and the source is WGSL.
+    ///
+    /// Naga doesn't record which named constant an array length was written as, so these are also read from WGSL
+    /// source, letting array lengths refer to their constants. Without it, or when arrays of one length were
+    /// written in different ways, lengths are written as literals, with a warning for any which match a constant.
+    pub source: Option<ModuleSource>,
+}
+
+mod sealed {
+    pub trait SealedModule {}
+    impl SealedModule for naga::Module {}
+}
+
+/// An extension trait for `naga::Module` which exposes the functionality of this crate.
+///
+/// # Usage
+///
+/// ```
+/// use naga_to_tokenstream::{ModuleToTokens, ModuleToTokensConfig};
+///
+/// let my_module = naga::Module::default();
+/// let token_representation = my_module.to_tokens(ModuleToTokensConfig::default());
+/// ```
+pub trait ModuleToTokens: sealed::SealedModule {
+    /// Converts a module to a set of `syn` module items, representing the module, along with any diagnostics
+    /// describing items which couldn't be generated.
+    fn to_items_with_diagnostics(
+        &self,
+        cfg: ModuleToTokensConfig,
+    ) -> (Vec<syn::Item>, diagnostics::Diagnostics);
+    /// Converts a module to a set of `syn` module items, representing the module. Any diagnostics are discarded.
+    fn to_items(&self, cfg: ModuleToTokensConfig) -> Vec<syn::Item> {
+        self.to_items_with_diagnostics(cfg).0
+    }
+    /// Convenience method which calls `to_items` and then flattens the items to a single tokenstream.
+    fn to_tokens(&self, cfg: ModuleToTokensConfig) -> proc_macro2::TokenStream {
+        collect_tokenstream(self.to_items(cfg))
+    }
+}
+impl ModuleToTokens for naga::Module {
+    fn to_items_with_diagnostics(
+        &self,
+        mut cfg: ModuleToTokensConfig,
+    ) -> (Vec<syn::Item>, diagnostics::Diagnostics) {
+        if let Some(source) = cfg.source.as_ref().filter(|source| source.is_wgsl()) {
+            if cfg.doc_comments == docs::DocComments::default() {
+                cfg.doc_comments = docs::DocComments::from_wgsl(&source.code);
+            }
+        }
+
+        let mut items = Vec::new();
+        let mut diagnostics = diagnostics::Diagnostics::new();
+        let mut types = types::TypesDefinitions::new(self, cfg.structs_filter.clone(), &cfg);
+        types.report_array_sizes(self, &cfg, &mut diagnostics);
+
+        // Validate once, sharing the resulting info between every source generated
+        let info = validate_module(self, &cfg, &mut diagnostics);
+
+        // Globals
+        let globals = collect_tokenstream(globals::make_globals(self, &mut types, &cfg));
+        let globals: syn::File = syn::parse2(globals).unwrap();
+        let globals_str = format!("```rust\n{}\n```", prettyplease::unparse(&globals));
+        let globals_doc: proc_macro2::TokenStream = quote::quote! { #[doc = #globals_str] };
+        items.push(syn::parse_quote! {
+            #[allow(unused)]
+            #[doc = "Information about the globals within the module, exposed as constants and functions."]
+            #globals_doc
+            pub mod globals {
+                #[allow(unused)]
+                use super::*;
+
+                #globals
+            }
+        });
+
+        // Constants
+        let constants = collect_tokenstream(constants::make_constants(
+            self,
+            &mut types,
+            &cfg,
+            &mut diagnostics,
+        ));
+        let constants: syn::File = syn::parse2(constants).unwrap();
+        let constants_str = format!("```rust\n{}\n```", prettyplease::unparse(&constants));
+        let constants_doc: proc_macro2::TokenStream = quote::quote! { #[doc = #constants_str] };
+        items.push(syn::parse_quote! {
+            #[allow(unused)]
+            #[doc = "Information about the constants within the module, exposed as constants and functions."]
+            #constants_doc
+            pub mod constants {
+                #[allow(unused)]
+                use super::*;
+
+                #constants
+            }
+        });
+
+        // Overrides
+        let overrides = collect_tokenstream(overrides::make_overrides(
+            self,
+            &mut types,
+            &cfg,
+            &mut diagnostics,
+        ));
+        let overrides: syn::File = syn::parse2(overrides).unwrap();
+        let overrides_str = format!("```rust\n{}\n```", prettyplease::unparse(&overrides));
+        let overrides_doc: proc_macro2::TokenStream = quote::quote! { #[doc = #overrides_str] };
+        items.push(syn::parse_quote! {
+            #[allow(unused)]
+            #[doc = "Information about the pipeline-overridable constants within the module, exposed as constants and functions."]
+            #overrides_doc
+            pub mod overrides {
+                #[allow(unused)]
+                use super::*;
+
+                #overrides
+            }
+        });
+
+        // Entry Points
+        let entry_points = collect_tokenstream(entry_points::make_entry_points(
+            self,
+            info.as_ref(),
+            &mut types,
+            &cfg,
+            &mut diagnostics,
+        ));
+        let entry_points: syn::File = syn::parse2(entry_points).unwrap();
+        let entry_points_str = format!("```rust\n{}\n```", prettyplease::unparse(&entry_points));
+        let entry_points_doc: proc_macro2::TokenStream =
+            quote::quote! { #[doc = #entry_points_str] };
+        items.push(syn::parse_quote! {
+            #[allow(unused)]
+            #[doc = "Information about the entry points within the module, exposed as constants and functions."]
+            #entry_points_doc
+            pub mod entry_points {
+                #[allow(unused)]
+                use super::*;
+
+                #entry_points
+            }
+        });
+
+        // Types
+        let types = collect_tokenstream(types.definitions());
+        let types: syn::File = syn::parse2(types).unwrap();
+        let types_str = format!("```rust\n{}\n```", prettyplease::unparse(&types));
+        let types_doc: proc_macro2::TokenStream = quote::quote! { #[doc = #types_str] };
+        items.push(syn::parse_quote! {
+          #[allow(unused)]
+          #[doc = "Equivalent Rust definitions of the types defined in this module."]
+          #types_doc
+          pub mod types {
+            #[allow(unused)]
+            use super::*;
+
+            #types
+          }
+        });
+        // We use all the types from the types mod in other modules.
+        items.push(syn::parse_quote! {
+            #[allow(unused)]
+            use types::*;
+        });
+
+        #[cfg(not(feature = "minify"))]
+        if cfg.minify != MinifyLevel::None {
+            diagnostics.warn(String::from(
+                "the `minify` feature of `naga-to-tokenstream` isn't enabled, so sources weren't minified",
+            ));
+        }
+
+        // Source string
+        let src = match (&cfg.source, &info) {
+            (Some(source), _) if source.embed_verbatim => Some(source.code.clone()),
+            (_, Some(info)) => {
+                module_to_source(self, info, "the module", "SOURCE", &cfg, &mut diagnostics)
+            }
+            (_, None) => None,
+        };
+        if let Some(src) = src {
+            items.push(syn::parse_quote! {
+                #[doc = "The sourcecode for the shader, as a constant string."]
+                pub const SOURCE: &'static str = #src;
+            });
+        }
+
+        // The capabilities and features required by the shader
+        if let Some(info) = &info {
+            items.extend(capabilities::make_requirements(self, info, false, &cfg));
+        }
+
+        // Sources in other shader languages
+        if let Some(info) = &info {
+            items.extend(backends::make_backend_sources(
+                self,
+                info,
+                None,
+                None,
+                &cfg,
+                &mut diagnostics,
+            ));
+        }
+
+        (items, diagnostics)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+/// Gives a name based on the given one which isn't in the set of reserved names.
+fn unreserved(name: &str, reserved: &HashSet<String>) -> String {
+    let mut name = name.to_owned();
+    while reserved.contains(&name) {
+        name.push('x');
+    }
+    name
+}
+
+/// Renames the arguments and local variables of a function which would otherwise clash with reserved names.
+fn rename_function(function: &mut naga::Function, reserved: &HashSet<String>) {
+    let arguments = function
+        .arguments
+        .iter_mut()
+        .map(|argument| &mut argument.name);
+    let locals = function
+        .local_variables
+        .iter_mut()
+        .map(|(_, local)| &mut local.name);
+    for name in arguments
+        .chain(locals)
+        .chain([&mut function.name])
+        .flatten()
+    {
+        *name = unreserved(name, reserved);
+    }
+}
+
+/// Removes unused items from a module and shortens its identifiers, other than the names of entry points, overrides
+/// and globals with bindings, which generated constants refer to. Any shortened identifier which would clash with one
+/// of these names is changed, since the WGSL writer would otherwise rename whichever it saw second.
+pub(crate) fn minify_module(module: &mut naga::Module) {
+    // Every global is kept by compaction, in the same order, so their handles don't change
+    let bound_globals = module
+        .global_variables
+        .iter()
+        .filter(|(_, global)| global.binding.is_some())
+        .map(|(handle, global)| (handle, global.name.clone()))
+        .collect::<Vec<_>>();
+
+    wgsl_minifier::minify_module(module);
+
+    for (handle, name) in &bound_globals {
+        module.global_variables[*handle].name = name.clone();
+    }
+
+    let mut reserved = HashSet::new();
+    reserved.extend(bound_globals.into_iter().filter_map(|(_, name)| name));
+    reserved.extend(
+        module
+            .entry_points
+            .iter()
+            .map(|entry_point| entry_point.name.clone()),
+    );
+    reserved.extend(
+        module
+            .overrides
+            .iter()
+            .filter_map(|(_, override_)| override_.name.clone()),
+    );
+
+    for (_, global) in module.global_variables.iter_mut() {
+        if global.binding.is_none() {
+            global.name = global
+                .name
+                .as_deref()
+                .map(|name| unreserved(name, &reserved));
+        }
+    }
+    for (_, function) in module.functions.iter_mut() {
+        rename_function(function, &reserved);
+    }
+    for entry_point in &mut module.entry_points {
+        rename_function(&mut entry_point.function, &reserved);
+    }
+
+    // Types can't be changed in place, so are reinserted in the same order. Renamed types are given names unlike any
+    // other type's, so no two types become identical, and the handles of the types don't change.
+    let mut type_reserved = reserved.clone();
+    type_reserved.extend(module.types.iter().filter_map(|(_, ty)| ty.name.clone()));
+    let mut types = naga::UniqueArena::new();
+    for (handle, ty) in module.types.iter() {
+        let mut ty = ty.clone();
+        if let Some(name) = ty.name.as_mut().filter(|name| reserved.contains(*name)) {
+            *name = unreserved(name, &type_reserved);
+            type_reserved.insert(name.clone());
+        }
+        types.insert(ty, module.types.get_span(handle));
+    }
+    module.types = types;
+}
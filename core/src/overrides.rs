@@ -0,0 +1,285 @@
+use crate::{diagnostics::Diagnostics, types::TypesDefinitions, ModuleToTokensConfig};
+
+/// Gives the identifier used for an override within the generated `overrides` module, if representable.
+pub(crate) fn override_ident(
+    override_handle: naga::Handle<naga::Override>,
+    module: &naga::Module,
+) -> Option<syn::Ident> {
+    let name = module
+        .overrides
+        .try_get(override_handle)
+        .ok()?
+        .name
+        .as_ref()?;
+    syn::parse_str::<syn::Ident>(name).ok()
+}
+
+/// Converts a scalar expression which may reference overrides into a Rust expression, where each override is
+/// read from the value of `overrides`, an expression of type `Overrides`. If integer arithmetic within the expression
+/// overflows or divides by zero, `on_error` is evaluated instead. Along with the expression, gives whether `on_error`
+/// may be evaluated.
+pub(crate) fn override_expression_to_tokens(
+    handle: naga::Handle<naga::Expression>,
+    overrides: &proc_macro2::TokenStream,
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+    on_error: &proc_macro2::TokenStream,
+) -> Option<(proc_macro2::TokenStream, bool)> {
+    crate::expressions::scalar_expression_to_tokens(
+        handle,
+        module,
+        args,
+        &|override_handle| {
+            let ident = override_ident(override_handle, module)?;
+            Some(quote::quote! { #overrides.#ident() })
+        },
+        on_error,
+    )
+}
+
+/// Converts the default value of an override into a constant Rust expression, if the default is known without
+/// the user providing the values of any other overrides. References to other overrides are made through the
+/// `DEFAULT` constants within their modules. Overflow while evaluating the default fails compilation, as the default
+/// is evaluated as a constant.
+pub(crate) fn override_default_to_tokens(
+    override_: &naga::Override,
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+) -> Option<proc_macro2::TokenStream> {
+    let message = format!(
+        "the default value of the `{}` override overflowed or divided by zero",
+        override_.name.as_deref().unwrap_or_default()
+    );
+    crate::expressions::scalar_expression_to_tokens(
+        override_.init?,
+        module,
+        args,
+        &|override_handle| {
+            let other = module.overrides.try_get(override_handle).ok()?;
+            override_default_to_tokens(other, module, args)?;
+            let ident = override_ident(override_handle, module)?;
+            Some(quote::quote! { #ident::DEFAULT })
+        },
+        &quote::quote! { panic!(#message) },
+    )
+    .map(|(default, _)| default)
+}
+
+/// Converts an override in a module into a collection of Rust definitions including the name, ID, type and
+/// default value of the override, if representable.
+pub fn make_override(
+    override_: &naga::Override,
+    module: &naga::Module,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    if let Some(name) = &override_.name {
+        items.push(syn::Item::Const(syn::parse_quote! {
+            pub const NAME: &'static str = #name;
+        }));
+    }
+
+    if let Some(id) = override_.id {
+        items.push(syn::Item::Const(syn::parse_quote! {
+            pub const ID: u16 = #id;
+        }));
+    }
+
+    if let Some(ty_ident) = types.rust_type_ident(override_.ty, module, args) {
+        items.push(syn::Item::Type(syn::parse_quote! {
+            pub type Ty = #ty_ident;
+        }));
+
+        if let Some(default) = override_default_to_tokens(override_, module, args) {
+            items.push(syn::Item::Const(syn::parse_quote! {
+                pub const DEFAULT: #ty_ident = #default;
+            }));
+        }
+    }
+
+    items
+}
+
+/// Builds a collection of overrides into a collection of Rust module definitions containing each of the overrides'
+/// properties, along with an `Overrides` struct holding a value for each of them.
+pub fn make_overrides(
+    module: &naga::Module,
+    types: &mut TypesDefinitions,
+    args: &ModuleToTokensConfig,
+    diagnostics: &mut Diagnostics,
+) -> Vec<syn::Item> {
+    let mut items = Vec::new();
+
+    let mut fields = Vec::new();
+    let mut accessors = Vec::new();
+    let mut constants = Vec::new();
+    let mut all_have_defaults = true;
+
+    for (override_handle, override_) in module.overrides.iter() {
+        let Some(ident) = override_ident(override_handle, module) else {
+            continue;
+        };
+        let name = override_.name.as_ref().expect("override had an identifier");
+
+        // Info about each override individually
+        let override_items =
+            crate::collect_tokenstream(make_override(override_, module, types, args));
+        let doc = format!(
+            "Information about the `{}` override within this shader module.{}",
+            name,
+            crate::declared_at(module.overrides.get_span(override_handle), args)
+        );
+        let doc = args.doc_comments.item_attributes(name, Some(&doc));
+        items.push(syn::parse_quote! {
+            #doc
+            #[allow(non_snake_case)]
+            pub mod #ident {
+                #[allow(unused)]
+                use super::*;
+
+                #override_items
+            }
+        });
+
+        // The override's entry in the `Overrides` struct
+        let Some(ty) = types.rust_type_ident(override_.ty, module, args) else {
+            let start = diagnostics.len();
+            diagnostics.warn(format!(
+                "the type of the `{}` override has no Rust equivalent, so it was left out of `Overrides` and `to_constants`, and must be given to pipelines by hand",
+                name
+            ));
+            diagnostics.locate_since(
+                start,
+                crate::source_location(module.overrides.get_span(override_handle), args),
+            );
+            continue;
+        };
+
+        // Pipeline constants are keyed by their ID if they have one, and their name otherwise
+        let key = match override_.id {
+            Some(id) => id.to_string(),
+            None => name.clone(),
+        };
+        let as_f64 = match module.types.get_handle(override_.ty) {
+            Ok(naga::Type {
+                inner:
+                    naga::TypeInner::Scalar(naga::Scalar {
+                        kind: naga::ScalarKind::Bool,
+                        ..
+                    }),
+                ..
+            }) => quote::quote! { if value { 1.0 } else { 0.0 } },
+            _ => quote::quote! { value as f64 },
+        };
+
+        // Defaults which depend on other overrides can only be evaluated once their values are known, so may fail
+        let message = format!(
+            "the default value of the `{}` override overflowed or divided by zero",
+            name
+        );
+        let on_error = quote::quote! { panic!(#message) };
+        let default = override_.init.and_then(|init| {
+            override_expression_to_tokens(init, &quote::quote!(self), module, args, &on_error)
+        });
+
+        match default {
+            Some((default, default_may_panic)) => {
+                let field_doc = format!(
+                    "The value of the `{}` override, or `None` to use the default value given in the shader.",
+                    name
+                );
+                fields.push(quote::quote! {
+                    #[doc = #field_doc]
+                    pub #ident: Option<#ty>
+                });
+                let mut accessor_doc = format!(
+                    "Gives the value of the `{}` override, falling back to the default value given in the shader.",
+                    name
+                );
+                if default_may_panic {
+                    accessor_doc.push_str(" Panics if the default value overflows or divides by zero given the values of the other overrides, which WGSL treats as an error when creating a pipeline.");
+                }
+                accessors.push(quote::quote! {
+                    #[doc = #accessor_doc]
+                    pub fn #ident(&self) -> #ty {
+                        match self.#ident {
+                            Some(value) => value,
+                            None => #default,
+                        }
+                    }
+                });
+                constants.push(quote::quote! {
+                    if let Some(value) = self.#ident {
+                        constants.insert(String::from(#key), #as_f64);
+                    }
+                });
+            }
+            None => {
+                all_have_defaults = false;
+
+                let field_doc = format!("The value of the `{}` override.", name);
+                fields.push(quote::quote! {
+                    #[doc = #field_doc]
+                    pub #ident: #ty
+                });
+                let accessor_doc = format!("Gives the value of the `{}` override.", name);
+                accessors.push(quote::quote! {
+                    #[doc = #accessor_doc]
+                    pub fn #ident(&self) -> #ty {
+                        self.#ident
+                    }
+                });
+                constants.push(quote::quote! {
+                    let value = self.#ident;
+                    constants.insert(String::from(#key), #as_f64);
+                });
+            }
+        }
+    }
+
+    // Info about all overrides together
+    let mut derives = quote::quote!(Debug, PartialEq, Clone, Copy,);
+    if all_have_defaults {
+        derives.extend(quote::quote!(Default,));
+    }
+    if args.gen_serde {
+        derives.extend(quote::quote!(serde::Serialize, serde::Deserialize,));
+    }
+
+    let mut doc = String::from(
+        "Values for each of the pipeline-overridable constants within the shader module.",
+    );
+    if module
+        .entry_points
+        .iter()
+        .any(|entry_point| entry_point.workgroup_size_overrides.is_some())
+    {
+        doc.push_str(" Entry points whose workgroup sizes depend on overrides have a `workgroup_size` function which evaluates the size from these values, giving `None` instead of `[u32; 3]` if the size is computed with integer arithmetic which may overflow or divide by zero.");
+    }
+    items.push(syn::parse_quote! {
+        #[doc = #doc]
+        #[allow(unused, non_snake_case)]
+        #[derive(#derives)]
+        pub struct Overrides {
+            #(#fields ,)*
+        }
+    });
+    items.push(syn::parse_quote! {
+        #[allow(unused, non_snake_case)]
+        impl Overrides {
+            #(#accessors)*
+
+            #[doc = "Gives the values of the overrides which have been set, in the form expected by `wgpu::PipelineCompilationOptions::constants`. Overrides left as `None` are omitted, so that the shader uses their default values."]
+            #[allow(clippy::unnecessary_cast)]
+            pub fn to_constants(&self) -> std::collections::HashMap<String, f64> {
+                let mut constants = std::collections::HashMap::new();
+                #(#constants)*
+                constants
+            }
+        }
+    });
+
+    items
+}
@@ -0,0 +1,435 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::diagnostics::Diagnostics;
+
+/// The functions, global variables, constants and overrides which an entry point can reach, either directly or
+/// through the functions it calls and the constant expressions it uses.
+#[derive(Default)]
+pub(crate) struct Reachable {
+    pub(crate) functions: HashSet<naga::Handle<naga::Function>>,
+    pub(crate) globals: HashSet<naga::Handle<naga::GlobalVariable>>,
+    pub(crate) constants: HashSet<naga::Handle<naga::Constant>>,
+    pub(crate) overrides: HashSet<naga::Handle<naga::Override>>,
+    global_expressions: HashSet<naga::Handle<naga::Expression>>,
+}
+
+impl Reachable {
+    /// Finds everything reachable from the given entry point.
+    ///
+    /// Types aren't traced, so any constants or overrides used to size arrays are treated as reachable, even if the
+    /// arrays themselves aren't used by the entry point.
+    pub(crate) fn from_entry_point(module: &naga::Module, entry_point: &naga::EntryPoint) -> Self {
+        let mut reachable = Self::default();
+        reachable.visit_function(module, &entry_point.function);
+
+        for size in entry_point
+            .workgroup_size_overrides
+            .iter()
+            .flatten()
+            .flatten()
+        {
+            reachable.visit_global_expression(module, *size);
+        }
+        for global in reachable.globals.clone() {
+            if let Some(init) = module.global_variables[global].init {
+                reachable.visit_global_expression(module, init);
+            }
+        }
+        for (_, ty) in module.types.iter() {
+            if let naga::TypeInner::Array {
+                size: naga::ArraySize::Pending(size),
+                ..
+            }
+            | naga::TypeInner::BindingArray {
+                size: naga::ArraySize::Pending(size),
+                ..
+            } = ty.inner
+            {
+                match size {
+                    naga::PendingArraySize::Expression(size) => {
+                        reachable.visit_global_expression(module, size)
+                    }
+                    naga::PendingArraySize::Override(size) => {
+                        reachable.visit_override(module, size)
+                    }
+                }
+            }
+        }
+
+        reachable
+    }
+
+    fn visit_function(&mut self, module: &naga::Module, function: &naga::Function) {
+        for (_, expression) in function.expressions.iter() {
+            match *expression {
+                naga::Expression::GlobalVariable(global) => {
+                    self.globals.insert(global);
+                }
+                naga::Expression::Constant(constant) => self.visit_constant(module, constant),
+                naga::Expression::Override(override_) => self.visit_override(module, override_),
+                naga::Expression::CallResult(callee) => self.visit_callee(module, callee),
+                _ => {}
+            }
+        }
+        self.visit_block(module, &function.body);
+    }
+
+    fn visit_callee(&mut self, module: &naga::Module, callee: naga::Handle<naga::Function>) {
+        if self.functions.insert(callee) {
+            self.visit_function(module, &module.functions[callee]);
+        }
+    }
+
+    fn visit_block(&mut self, module: &naga::Module, block: &naga::Block) {
+        for statement in block.iter() {
+            match statement {
+                naga::Statement::Call { function, .. } => self.visit_callee(module, *function),
+                naga::Statement::Block(block) => self.visit_block(module, block),
+                naga::Statement::If { accept, reject, .. } => {
+                    self.visit_block(module, accept);
+                    self.visit_block(module, reject);
+                }
+                naga::Statement::Switch { cases, .. } => {
+                    for case in cases {
+                        self.visit_block(module, &case.body);
+                    }
+                }
+                naga::Statement::Loop {
+                    body, continuing, ..
+                } => {
+                    self.visit_block(module, body);
+                    self.visit_block(module, continuing);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_constant(&mut self, module: &naga::Module, constant: naga::Handle<naga::Constant>) {
+        if self.constants.insert(constant) {
+            self.visit_global_expression(module, module.constants[constant].init);
+        }
+    }
+
+    fn visit_override(&mut self, module: &naga::Module, override_: naga::Handle<naga::Override>) {
+        if self.overrides.insert(override_) {
+            if let Some(init) = module.overrides[override_].init {
+                self.visit_global_expression(module, init);
+            }
+        }
+    }
+
+    /// Visits a constant expression, along with the constants, overrides and other expressions that it refers to.
+    /// Only the kinds of expression which can be evaluated at module scope are followed.
+    fn visit_global_expression(
+        &mut self,
+        module: &naga::Module,
+        expression: naga::Handle<naga::Expression>,
+    ) {
+        if !self.global_expressions.insert(expression) {
+            return;
+        }
+
+        let mut operands = Vec::new();
+        match module.global_expressions[expression] {
+            naga::Expression::Constant(constant) => self.visit_constant(module, constant),
+            naga::Expression::Override(override_) => self.visit_override(module, override_),
+            naga::Expression::Compose { ref components, .. } => {
+                operands.extend(components.iter().copied())
+            }
+            naga::Expression::Access { base, index } => operands.extend([base, index]),
+            naga::Expression::AccessIndex { base, .. } => operands.push(base),
+            naga::Expression::Splat { value, .. } => operands.push(value),
+            naga::Expression::Swizzle { vector, .. } => operands.push(vector),
+            naga::Expression::Unary { expr, .. }
+            | naga::Expression::As { expr, .. }
+            | naga::Expression::Relational { argument: expr, .. } => operands.push(expr),
+            naga::Expression::Binary { left, right, .. } => operands.extend([left, right]),
+            naga::Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => operands.extend([condition, accept, reject]),
+            naga::Expression::Math {
+                arg,
+                arg1,
+                arg2,
+                arg3,
+                ..
+            } => {
+                operands.push(arg);
+                operands.extend([arg1, arg2, arg3].into_iter().flatten());
+            }
+            _ => {}
+        }
+        for operand in operands {
+            self.visit_global_expression(module, operand);
+        }
+    }
+}
+
+/// Keeps only the items of an arena which satisfy a predicate, in the same order, giving the new handles of the items
+/// kept.
+fn retain_in_arena<T>(
+    arena: &mut naga::Arena<T>,
+    keep: impl Fn(naga::Handle<T>) -> bool,
+) -> HashMap<naga::Handle<T>, naga::Handle<T>> {
+    let mut kept = naga::Arena::new();
+    let mut handles = HashMap::new();
+    for (handle, item, span) in arena.drain() {
+        if keep(handle) {
+            handles.insert(handle, kept.append(item, span));
+        }
+    }
+    *arena = kept;
+    handles
+}
+
+/// Removes every entry point other than the one with the given name, along with any functions, global variables,
+/// constants, overrides and types which that entry point can't reach, so that the module is as small as possible.
+///
+/// If something reachable refers to an item which wasn't found to be reachable, a warning is given and only the other
+/// entry points are removed, leaving everything else in place.
+pub(crate) fn retain_reachable(
+    module: &mut naga::Module,
+    entry_point: &str,
+    diagnostics: &mut Diagnostics,
+) {
+    crate::entry_points::filter_entry_points(module, entry_point.to_owned());
+    let reachable = match module.entry_points.first() {
+        Some(entry_point) => Reachable::from_entry_point(module, entry_point),
+        None => Reachable::default(),
+    };
+    let unfiltered = module.clone();
+
+    // Keeping the order of each arena keeps the module valid, since items can only refer to those declared before
+    let mut remap = Remap {
+        functions: retain_in_arena(&mut module.functions, |handle| {
+            reachable.functions.contains(&handle)
+        }),
+        globals: retain_in_arena(&mut module.global_variables, |handle| {
+            reachable.globals.contains(&handle)
+        }),
+        constants: retain_in_arena(&mut module.constants, |handle| {
+            reachable.constants.contains(&handle)
+        }),
+        overrides: retain_in_arena(&mut module.overrides, |handle| {
+            reachable.overrides.contains(&handle)
+        }),
+        missing: false,
+    };
+
+    for (_, function) in module.functions.iter_mut() {
+        remap.function(function);
+    }
+    for entry_point in &mut module.entry_points {
+        remap.function(&mut entry_point.function);
+    }
+    // Unreachable expressions may refer to items which have been removed, but are about to be compacted away
+    for (handle, expression) in module.global_expressions.iter_mut() {
+        if reachable.global_expressions.contains(&handle) {
+            remap.expression(expression);
+        }
+    }
+    remap.types(module);
+
+    if remap.missing {
+        diagnostics.warn(format!(
+            "failed to find everything which the entry point `{}` uses, so its exclusive sources keep every function, global, constant, override and type in the module",
+            entry_point
+        ));
+        *module = unfiltered;
+        naga::compact::compact(module);
+        return;
+    }
+
+    // naga keeps every named type when compacting, so the names of types which are no longer used are removed first,
+    // letting a single compaction remove them along with the unreachable expressions
+    unname_unused_types(module, &reachable.global_expressions);
+    naga::compact::compact(module);
+}
+
+/// Finds the types used by a module's global variables, constants, overrides, functions, entry points and the given
+/// global expressions, along with the types those types contain.
+fn used_types(
+    module: &naga::Module,
+    global_expressions: &HashSet<naga::Handle<naga::Expression>>,
+) -> HashSet<naga::Handle<naga::Type>> {
+    let mut used = HashSet::new();
+    used.extend(module.global_variables.iter().map(|(_, global)| global.ty));
+    used.extend(module.constants.iter().map(|(_, constant)| constant.ty));
+    used.extend(module.overrides.iter().map(|(_, override_)| override_.ty));
+    used.extend(module.special_types.ray_desc);
+    used.extend(module.special_types.ray_intersection);
+    used.extend(module.special_types.predeclared_types.values().copied());
+
+    let expression_type = |expression: &naga::Expression| match *expression {
+        naga::Expression::ZeroValue(ty)
+        | naga::Expression::Compose { ty, .. }
+        | naga::Expression::AtomicResult { ty, .. }
+        | naga::Expression::WorkGroupUniformLoadResult { ty }
+        | naga::Expression::SubgroupOperationResult { ty } => Some(ty),
+        _ => None,
+    };
+    used.extend(
+        module
+            .global_expressions
+            .iter()
+            .filter(|(handle, _)| global_expressions.contains(handle))
+            .filter_map(|(_, expression)| expression_type(expression)),
+    );
+    let functions = module.functions.iter().map(|(_, function)| function);
+    let entry_points = module
+        .entry_points
+        .iter()
+        .map(|entry_point| &entry_point.function);
+    for function in functions.chain(entry_points) {
+        used.extend(function.arguments.iter().map(|argument| argument.ty));
+        used.extend(function.result.iter().map(|result| result.ty));
+        used.extend(function.local_variables.iter().map(|(_, local)| local.ty));
+        used.extend(
+            function
+                .expressions
+                .iter()
+                .filter_map(|(_, expression)| expression_type(expression)),
+        );
+    }
+
+    // Types can only contain those declared before them, so a single backwards pass finds every contained type
+    for (handle, ty) in module.types.iter().rev() {
+        if !used.contains(&handle) {
+            continue;
+        }
+        match ty.inner {
+            naga::TypeInner::Pointer { base, .. }
+            | naga::TypeInner::Array { base, .. }
+            | naga::TypeInner::BindingArray { base, .. } => {
+                used.insert(base);
+            }
+            naga::TypeInner::Struct { ref members, .. } => {
+                used.extend(members.iter().map(|member| member.ty));
+            }
+            _ => {}
+        }
+    }
+
+    used
+}
+
+/// Removes the names of types which aren't used by anything other than global expressions outside of those given, so
+/// that they can be compacted away. Types are reinserted in the same order, and a name is kept if removing it would
+/// make the type identical to another, so the handles of the types don't change.
+fn unname_unused_types(
+    module: &mut naga::Module,
+    global_expressions: &HashSet<naga::Handle<naga::Expression>>,
+) {
+    let used = used_types(module, global_expressions);
+    let mut types = naga::UniqueArena::new();
+    for (handle, ty) in module.types.iter() {
+        let mut ty = ty.clone();
+        if !used.contains(&handle) && ty.name.is_some() {
+            let unnamed = naga::Type {
+                name: None,
+                inner: ty.inner.clone(),
+            };
+            if module.types.get(&unnamed).is_none() && types.get(&unnamed).is_none() {
+                ty = unnamed;
+            }
+        }
+        types.insert(ty, module.types.get_span(handle));
+    }
+    module.types = types;
+}
+
+/// The new handles of the items kept by `retain_reachable`.
+struct Remap {
+    functions: HashMap<naga::Handle<naga::Function>, naga::Handle<naga::Function>>,
+    globals: HashMap<naga::Handle<naga::GlobalVariable>, naga::Handle<naga::GlobalVariable>>,
+    constants: HashMap<naga::Handle<naga::Constant>, naga::Handle<naga::Constant>>,
+    overrides: HashMap<naga::Handle<naga::Override>, naga::Handle<naga::Override>>,
+    /// Whether a handle to an item which wasn't kept was found, in which case it was left unchanged.
+    missing: bool,
+}
+
+/// Replaces a handle with its new handle, if the item it refers to was kept, giving whether it was.
+fn remap_handle<T>(
+    handles: &HashMap<naga::Handle<T>, naga::Handle<T>>,
+    handle: &mut naga::Handle<T>,
+) -> bool {
+    match handles.get(handle) {
+        Some(new) => {
+            *handle = *new;
+            true
+        }
+        None => false,
+    }
+}
+
+impl Remap {
+    fn function(&mut self, function: &mut naga::Function) {
+        for (_, expression) in function.expressions.iter_mut() {
+            self.expression(expression);
+        }
+        self.block(&mut function.body);
+    }
+
+    fn expression(&mut self, expression: &mut naga::Expression) {
+        let kept = match expression {
+            naga::Expression::GlobalVariable(global) => remap_handle(&self.globals, global),
+            naga::Expression::Constant(constant) => remap_handle(&self.constants, constant),
+            naga::Expression::Override(override_) => remap_handle(&self.overrides, override_),
+            naga::Expression::CallResult(callee) => remap_handle(&self.functions, callee),
+            _ => true,
+        };
+        self.missing |= !kept;
+    }
+
+    fn block(&mut self, block: &mut naga::Block) {
+        for statement in block.iter_mut() {
+            match statement {
+                naga::Statement::Call { function, .. } => {
+                    self.missing |= !remap_handle(&self.functions, function)
+                }
+                naga::Statement::Block(block) => self.block(block),
+                naga::Statement::If { accept, reject, .. } => {
+                    self.block(accept);
+                    self.block(reject);
+                }
+                naga::Statement::Switch { cases, .. } => {
+                    for case in cases {
+                        self.block(&mut case.body);
+                    }
+                }
+                naga::Statement::Loop {
+                    body, continuing, ..
+                } => {
+                    self.block(body);
+                    self.block(continuing);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Updates arrays sized by overrides. Types can't be changed in place, so are reinserted in the same order, which
+    /// keeps the handles of the types themselves unchanged.
+    fn types(&mut self, module: &mut naga::Module) {
+        let mut types = naga::UniqueArena::new();
+        for (handle, ty) in module.types.iter() {
+            let mut ty = ty.clone();
+            if let naga::TypeInner::Array {
+                size: naga::ArraySize::Pending(naga::PendingArraySize::Override(ref mut size)),
+                ..
+            }
+            | naga::TypeInner::BindingArray {
+                size: naga::ArraySize::Pending(naga::PendingArraySize::Override(ref mut size)),
+                ..
+            } = ty.inner
+            {
+                self.missing |= !remap_handle(&self.overrides, size);
+            }
+            types.insert(ty, module.types.get_span(handle));
+        }
+        module.types = types;
+    }
+}
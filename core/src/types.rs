@@ -0,0 +1,966 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::TokenStream;
+
+use crate::{diagnostics::Diagnostics, ModuleToTokensConfig};
+
+/// Returns a base Rust or `glam` type that corresponds to a TypeInner, if one exists.
+pub(crate) fn rust_type(
+    type_inner: &naga::TypeInner,
+    args: &ModuleToTokensConfig,
+) -> Option<syn::Type> {
+    match type_inner {
+        naga::TypeInner::Scalar(naga::Scalar { kind, width }) => match (kind, width) {
+            (naga::ScalarKind::Bool, 1) => Some(syn::parse_quote!(bool)),
+            (naga::ScalarKind::Float, 4) => Some(syn::parse_quote!(f32)),
+            (naga::ScalarKind::Float, 8) => Some(syn::parse_quote!(f64)),
+            (naga::ScalarKind::Sint, 4) => Some(syn::parse_quote!(i32)),
+            (naga::ScalarKind::Sint, 8) => Some(syn::parse_quote!(i64)),
+            (naga::ScalarKind::Uint, 4) => Some(syn::parse_quote!(u32)),
+            (naga::ScalarKind::Uint, 8) => Some(syn::parse_quote!(u64)),
+            _ => None,
+        },
+        naga::TypeInner::Vector {
+            size,
+            scalar: naga::Scalar { kind, width },
+        } => {
+            if args.gen_glam {
+                match (size, kind, width) {
+                    (naga::VectorSize::Bi, naga::ScalarKind::Bool, 1) => {
+                        Some(syn::parse_quote!(glam::bool::BVec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Bool, 1) => {
+                        Some(syn::parse_quote!(glam::bool::BVec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Bool, 1) => {
+                        Some(syn::parse_quote!(glam::bool::BVec4))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Float, 4) => {
+                        Some(syn::parse_quote!(glam::f32::Vec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Float, 4) => {
+                        Some(syn::parse_quote!(glam::f32::Vec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Float, 4) => {
+                        Some(syn::parse_quote!(glam::f32::Vec4))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Float, 8) => {
+                        Some(syn::parse_quote!(glam::f64::DVec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Float, 8) => {
+                        Some(syn::parse_quote!(glam::f64::DVec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Float, 8) => {
+                        Some(syn::parse_quote!(glam::f64::DVec4))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Sint, 4) => {
+                        Some(syn::parse_quote!(glam::i32::IVec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Sint, 4) => {
+                        Some(syn::parse_quote!(glam::i32::IVec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Sint, 4) => {
+                        Some(syn::parse_quote!(glam::i32::IVec4))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Sint, 8) => {
+                        Some(syn::parse_quote!(glam::i64::I64Vec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Sint, 8) => {
+                        Some(syn::parse_quote!(glam::i64::I64Vec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Sint, 8) => {
+                        Some(syn::parse_quote!(glam::i64::I64Vec4))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Uint, 4) => {
+                        Some(syn::parse_quote!(glam::u32::UVec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Uint, 4) => {
+                        Some(syn::parse_quote!(glam::u32::UVec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Uint, 4) => {
+                        Some(syn::parse_quote!(glam::u32::UVec4))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Uint, 8) => {
+                        Some(syn::parse_quote!(glam::u64::U64Vec2))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Uint, 8) => {
+                        Some(syn::parse_quote!(glam::u64::U64Vec3))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Uint, 8) => {
+                        Some(syn::parse_quote!(glam::u64::U64Vec4))
+                    }
+                    _ => None,
+                }
+            } else {
+                match (size, kind, width) {
+                    (naga::VectorSize::Bi, naga::ScalarKind::Bool, 1) => {
+                        Some(syn::parse_quote!([bool; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Bool, 1) => {
+                        Some(syn::parse_quote!([bool; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Bool, 1) => {
+                        Some(syn::parse_quote!([bool; 4]))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Float, 4) => {
+                        Some(syn::parse_quote!([f32; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Float, 4) => {
+                        Some(syn::parse_quote!([f32; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Float, 4) => {
+                        Some(syn::parse_quote!([f32; 4]))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Float, 8) => {
+                        Some(syn::parse_quote!([f64; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Float, 8) => {
+                        Some(syn::parse_quote!([f64; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Float, 8) => {
+                        Some(syn::parse_quote!([f64; 4]))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Sint, 4) => {
+                        Some(syn::parse_quote!([i32; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Sint, 4) => {
+                        Some(syn::parse_quote!([i32; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Sint, 4) => {
+                        Some(syn::parse_quote!([i32; 4]))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Sint, 8) => {
+                        Some(syn::parse_quote!([i64; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Sint, 8) => {
+                        Some(syn::parse_quote!([i64; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Sint, 8) => {
+                        Some(syn::parse_quote!([i64; 4]))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Uint, 4) => {
+                        Some(syn::parse_quote!([u32; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Uint, 4) => {
+                        Some(syn::parse_quote!([u32; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Uint, 4) => {
+                        Some(syn::parse_quote!([u32; 4]))
+                    }
+                    (naga::VectorSize::Bi, naga::ScalarKind::Uint, 8) => {
+                        Some(syn::parse_quote!([u64; 2]))
+                    }
+                    (naga::VectorSize::Tri, naga::ScalarKind::Uint, 8) => {
+                        Some(syn::parse_quote!([u64; 3]))
+                    }
+                    (naga::VectorSize::Quad, naga::ScalarKind::Uint, 8) => {
+                        Some(syn::parse_quote!([u64; 4]))
+                    }
+                    _ => None,
+                }
+            }
+        }
+        naga::TypeInner::Matrix {
+            columns,
+            rows,
+            scalar: naga::Scalar { kind, width },
+        } => {
+            if !args.gen_glam {
+                return None;
+            }
+            if columns != rows {
+                return None;
+            }
+            match (kind, width) {
+                (naga::ScalarKind::Float, 4) => match columns {
+                    naga::VectorSize::Bi => Some(syn::parse_quote!(glam::f32::Mat2)),
+                    naga::VectorSize::Tri => Some(syn::parse_quote!(glam::f32::Mat3)),
+                    naga::VectorSize::Quad => Some(syn::parse_quote!(glam::f32::Mat4)),
+                },
+                (naga::ScalarKind::Float, 8) => match columns {
+                    naga::VectorSize::Bi => Some(syn::parse_quote!(glam::f64::Mat2)),
+                    naga::VectorSize::Tri => Some(syn::parse_quote!(glam::f64::Mat3)),
+                    naga::VectorSize::Quad => Some(syn::parse_quote!(glam::f64::Mat4)),
+                },
+                _ => None,
+            }
+        }
+        naga::TypeInner::Atomic(scalar) => rust_type(&naga::TypeInner::Scalar(*scalar), args),
+        _ => None,
+    }
+}
+
+/// How the lengths of fixed-size arrays were written in a module's WGSL source. Naga evaluates array lengths when
+/// parsing, without keeping any record of a named constant that a length was written as, and merges arrays of equal
+/// lengths into one type. So the only way to find these constants is to read the lengths from the source code.
+#[derive(Debug, Clone)]
+enum ArraySizes {
+    /// No WGSL source was given, so every length is written as a literal.
+    NoSource,
+    /// Some length in the source couldn't be evaluated, so any array could have been written with it, and every
+    /// length is written as a literal.
+    Unknown,
+    /// How the arrays of each length were written.
+    Known(HashMap<u32, ArraySizeSpelling>),
+}
+
+/// How every array of one length was written in a module's WGSL source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArraySizeSpelling {
+    /// Every array of this length was written with an integer literal.
+    Literal,
+    /// Every array of this length was written as the same named constant.
+    Constant(naga::Handle<naga::Constant>),
+    /// The arrays of this length were written in more than one way.
+    Mixed,
+}
+
+impl ArraySizes {
+    /// Reads the lengths of the arrays written in the module's source, if a WGSL source was given.
+    fn new(module: &naga::Module, args: &ModuleToTokensConfig) -> Self {
+        let Some(source) = args.source.as_ref().filter(|source| source.is_wgsl()) else {
+            return ArraySizes::NoSource;
+        };
+
+        let mut sizes = HashMap::new();
+        for size_text in array_size_texts(&crate::strip_comments(&source.code, false)) {
+            let constant = module
+                .constants
+                .iter()
+                .find(|(_, constant)| constant.name.as_deref() == Some(size_text));
+            let (value, spelling) = match constant {
+                Some((handle, constant)) => (
+                    constant_u32(constant, module),
+                    ArraySizeSpelling::Constant(handle),
+                ),
+                // Arrays sized by overrides aren't given constant lengths, so can't collide
+                None if module
+                    .overrides
+                    .iter()
+                    .any(|(_, o)| o.name.as_deref() == Some(size_text)) =>
+                {
+                    continue
+                }
+                None => (integer_literal(size_text), ArraySizeSpelling::Literal),
+            };
+            let Some(value) = value else {
+                return ArraySizes::Unknown;
+            };
+            sizes
+                .entry(value)
+                .and_modify(|existing| {
+                    if *existing != spelling {
+                        *existing = ArraySizeSpelling::Mixed
+                    }
+                })
+                .or_insert(spelling);
+        }
+        ArraySizes::Known(sizes)
+    }
+
+    /// Gives the named constant which every array of the given length was written as, if any.
+    fn constant(&self, size: u32) -> Option<naga::Handle<naga::Constant>> {
+        match self {
+            ArraySizes::Known(sizes) => match sizes.get(&size)? {
+                ArraySizeSpelling::Constant(handle) => Some(*handle),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Explains why an array length which matches the value of a named constant isn't written as that constant,
+    /// if it may have been written as it in the source.
+    fn fallback_reason(&self, size: u32) -> Option<&'static str> {
+        match self {
+            ArraySizes::NoSource => Some("the WGSL source of the module wasn't given in `ModuleToTokensConfig::source`"),
+            ArraySizes::Unknown => Some("the length of some array in the WGSL source couldn't be evaluated"),
+            ArraySizes::Known(sizes) => match sizes.get(&size)? {
+                ArraySizeSpelling::Mixed => Some("arrays of this length were written in more than one way, and naga merges them into one type"),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Gives the value of an integer constant, if it fits in a `u32`.
+fn constant_u32(constant: &naga::Constant, module: &naga::Module) -> Option<u32> {
+    let naga::TypeInner::Scalar(scalar) = module.types.get_handle(constant.ty).ok()?.inner else {
+        return None;
+    };
+    let value = match crate::expressions::evaluate(constant.init, module)? {
+        crate::expressions::ConstValue::Scalar(lit) => {
+            crate::expressions::concretize_literal(lit, scalar)?
+        }
+        crate::expressions::ConstValue::Composite(_) => return None,
+    };
+    match value {
+        naga::Literal::U32(v) => Some(v),
+        naga::Literal::I32(v) => v.try_into().ok(),
+        naga::Literal::U64(v) => v.try_into().ok(),
+        naga::Literal::I64(v) => v.try_into().ok(),
+        _ => None,
+    }
+}
+
+/// Parses a WGSL integer literal, such as `4`, `4u` or `0x10i`.
+fn integer_literal(text: &str) -> Option<u32> {
+    let text = text.trim_end_matches(['u', 'i']);
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Gives the text of the size of every fixed-size `array` and `binding_array` written in WGSL source code, which
+/// should have had its comments stripped.
+fn array_size_texts(code: &str) -> Vec<&str> {
+    let mut sizes = Vec::new();
+    for (start, _) in code.match_indices("array<") {
+        // Skip identifiers which only end in `array`, other than `binding_array`
+        let before = code[..start]
+            .strip_suffix("binding_")
+            .unwrap_or(&code[..start]);
+        if before
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            continue;
+        }
+
+        // Find the top-level comma and closing bracket of the template list
+        let args_start = start + "array<".len();
+        let mut depth = 0usize;
+        let mut comma = None;
+        let mut end = None;
+        for (i, c) in code[args_start..].char_indices() {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' if depth > 0 => depth -= 1,
+                '>' => {
+                    end = Some(args_start + i);
+                    break;
+                }
+                ',' if depth == 0 && comma.is_none() => comma = Some(args_start + i + 1),
+                ';' | '{' | '}' => break,
+                _ => {}
+            }
+        }
+        if let (Some(comma), Some(end)) = (comma, end) {
+            let size = code[comma..end].trim().trim_end_matches(',').trim_end();
+            if !size.is_empty() {
+                sizes.push(size);
+            }
+        }
+    }
+    sizes
+}
+
+/// Gives the names of the fields of the Rust struct generated for a struct with the given members. If any member is
+/// unnamed then all fields are named by their index instead.
+pub(crate) fn struct_member_idents(members: &[naga::StructMember]) -> Vec<Option<syn::Ident>> {
+    let members_have_names = members.iter().all(|member| member.name.is_some());
+    members
+        .iter()
+        .enumerate()
+        .map(|(i_member, member)| {
+            let member_name = if members_have_names {
+                let member_name = member.name.as_ref().expect("all members had names").clone();
+                syn::parse_str::<syn::Ident>(&member_name)
+            } else {
+                syn::parse_str::<syn::Ident>(&format!("v{}", i_member))
+            };
+            member_name.ok()
+        })
+        .collect()
+}
+
+/// Gives the overrides which the size of a type depends on, in the order they are first found. Returns `None` if
+/// any size depends on an override expression, rather than directly on an override.
+fn pending_overrides(
+    ty_handle: naga::Handle<naga::Type>,
+    module: &naga::Module,
+) -> Option<Vec<naga::Handle<naga::Override>>> {
+    let mut overrides = Vec::new();
+    let mut push_all = |new: Vec<naga::Handle<naga::Override>>| {
+        for override_handle in new {
+            if !overrides.contains(&override_handle) {
+                overrides.push(override_handle);
+            }
+        }
+    };
+
+    match &module.types.get_handle(ty_handle).ok()?.inner {
+        naga::TypeInner::Array { base, size, .. }
+        | naga::TypeInner::BindingArray { base, size } => {
+            match size {
+                naga::ArraySize::Pending(naga::PendingArraySize::Override(override_handle)) => {
+                    push_all(vec![*override_handle])
+                }
+                naga::ArraySize::Pending(naga::PendingArraySize::Expression(_)) => return None,
+                naga::ArraySize::Constant(_) | naga::ArraySize::Dynamic => {}
+            }
+            push_all(pending_overrides(*base, module)?);
+        }
+        naga::TypeInner::Struct { members, .. } => {
+            for member in members {
+                push_all(pending_overrides(member.ty, module)?);
+            }
+        }
+        _ => {}
+    }
+
+    Some(overrides)
+}
+
+/// Gives the length of an override-sized array when the override takes its default value, if known.
+fn default_array_size(
+    override_handle: naga::Handle<naga::Override>,
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+) -> Option<TokenStream> {
+    let override_ = module.overrides.try_get(override_handle).ok()?;
+    crate::overrides::override_default_to_tokens(override_, module, args)?;
+    let ident = crate::overrides::override_ident(override_handle, module)?;
+    Some(quote::quote!({ overrides::#ident::DEFAULT as usize }))
+}
+
+/// Gives a constant Rust expression for the zero value of a type, as defined by WGSL, where `rust_ty` is the Rust
+/// type that was generated for the type.
+fn zero_value(
+    ty_handle: naga::Handle<naga::Type>,
+    rust_ty: &syn::Type,
+    module: &naga::Module,
+    args: &ModuleToTokensConfig,
+) -> Option<TokenStream> {
+    match &module.types.get_handle(ty_handle).ok()?.inner {
+        naga::TypeInner::Scalar(scalar) | naga::TypeInner::Atomic(scalar) => Some(
+            crate::constants::literal_to_tokens(&naga::Literal::zero(*scalar)?),
+        ),
+        naga::TypeInner::Vector { size, scalar } => {
+            if args.gen_glam {
+                match scalar.kind {
+                    naga::ScalarKind::Bool => Some(quote::quote!(#rust_ty::FALSE)),
+                    _ => Some(quote::quote!(#rust_ty::ZERO)),
+                }
+            } else {
+                let zero = crate::constants::literal_to_tokens(&naga::Literal::zero(*scalar)?);
+                let size = *size as usize;
+                Some(quote::quote!([#zero; #size]))
+            }
+        }
+        naga::TypeInner::Matrix { .. } => Some(quote::quote!(#rust_ty::ZERO)),
+        naga::TypeInner::Array {
+            size: naga::ArraySize::Dynamic,
+            ..
+        }
+        | naga::TypeInner::BindingArray {
+            size: naga::ArraySize::Dynamic,
+            ..
+        } => Some(quote::quote!(Vec::new())),
+        naga::TypeInner::Array { base, .. } | naga::TypeInner::BindingArray { base, .. } => {
+            let syn::Type::Array(array) = rust_ty else {
+                return None;
+            };
+            let element = zero_value(*base, &array.elem, module, args)?;
+            let len = &array.len;
+            Some(quote::quote!([const { #element }; #len]))
+        }
+        naga::TypeInner::Struct { .. } => Some(quote::quote!(<#rust_ty>::zeroed())),
+        _ => None,
+    }
+}
+
+/// The traits which every generated struct derives.
+const BASE_DERIVES: [&str; 3] = ["Debug", "PartialEq", "Clone"];
+
+/// Gives the name of the trait being derived by a derive macro path, e.g. `Serialize` for `serde::Serialize`.
+fn derive_name(derive: &syn::Path) -> String {
+    derive
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default()
+}
+
+/// A builder for type definition and identifier pairs.
+pub struct TypesDefinitions {
+    definitions: Vec<syn::Item>,
+    references: HashMap<naga::Handle<naga::Type>, syn::Type>,
+    /// Structs which are generic over the overrides that their arrays are sized by, along with those overrides.
+    generic_structs:
+        HashMap<naga::Handle<naga::Type>, (syn::Ident, Vec<naga::Handle<naga::Override>>)>,
+    /// The names of the traits derived by each generated struct.
+    struct_derives: HashMap<naga::Handle<naga::Type>, HashSet<String>>,
+    /// How the lengths of fixed-size arrays were written in the module's source.
+    array_sizes: ArraySizes,
+    structs_filter: Option<HashSet<String>>,
+}
+
+impl TypesDefinitions {
+    /// Constructs a new type definition collator, with a given filter for type names.
+    pub fn new(
+        module: &naga::Module,
+        structs_filter: Option<HashSet<String>>,
+        args: &ModuleToTokensConfig,
+    ) -> Self {
+        let mut res = Self {
+            definitions: Vec::new(),
+            references: HashMap::new(),
+            generic_structs: HashMap::new(),
+            struct_derives: HashMap::new(),
+            array_sizes: ArraySizes::new(module, args),
+            structs_filter,
+        };
+
+        for (ty_handle, _) in module.types.iter() {
+            res.rust_type_ident(ty_handle, module, args);
+        }
+
+        res
+    }
+
+    fn try_make_type(
+        &mut self,
+        ty_handle: naga::Handle<naga::Type>,
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+    ) -> Option<syn::Type> {
+        // Anything sized by an override is given the size of that override's default value
+        self.make_type(ty_handle, module, args, &|override_handle| {
+            default_array_size(override_handle, module, args)
+        })
+    }
+
+    /// Whether the Rust type generated for a type implements the standard trait with the given name, so that structs
+    /// containing it can derive that trait. Traits which we don't know about are assumed to be implemented.
+    fn derive_holds(
+        &self,
+        derive_name: &str,
+        ty_handle: naga::Handle<naga::Type>,
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+    ) -> bool {
+        let Ok(ty) = module.types.get_handle(ty_handle) else {
+            return false;
+        };
+        let scalar_holds = |scalar: &naga::Scalar| match derive_name {
+            "Eq" | "Ord" | "Hash" => scalar.kind != naga::ScalarKind::Float,
+            _ => true,
+        };
+
+        match &ty.inner {
+            naga::TypeInner::Scalar(scalar) | naga::TypeInner::Atomic(scalar) => {
+                scalar_holds(scalar)
+            }
+            naga::TypeInner::Vector { scalar, .. } => {
+                if args.gen_glam {
+                    // Glam vectors have no ordering, and only implement `Eq` and `Hash` for integers and bools
+                    match derive_name {
+                        "Ord" | "PartialOrd" => false,
+                        _ => scalar_holds(scalar),
+                    }
+                } else {
+                    scalar_holds(scalar)
+                }
+            }
+            naga::TypeInner::Matrix { .. } => {
+                !matches!(derive_name, "Eq" | "Ord" | "PartialOrd" | "Hash")
+            }
+            naga::TypeInner::Array { base, size, .. }
+            | naga::TypeInner::BindingArray { base, size } => {
+                let size_holds = match (derive_name, size) {
+                    ("Copy", naga::ArraySize::Dynamic) => false,
+                    // The standard library and `serde` only implement these for small arrays of known sizes
+                    ("Default" | "Serialize" | "Deserialize", naga::ArraySize::Constant(size)) => {
+                        size.get() <= 32
+                    }
+                    ("Default" | "Serialize" | "Deserialize", naga::ArraySize::Pending(_)) => false,
+                    _ => true,
+                };
+                size_holds && self.derive_holds(derive_name, *base, module, args)
+            }
+            naga::TypeInner::Struct { .. } => match derive_name {
+                "Copy" | "Eq" | "Ord" | "PartialOrd" | "Hash" | "Default" | "Serialize"
+                | "Deserialize" => self
+                    .struct_derives
+                    .get(&ty_handle)
+                    .is_some_and(|derived| derived.contains(derive_name)),
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    /// Builds the Rust type of a member of another type. Types sized by overrides are given sizes by `sizes`.
+    fn make_member_type(
+        &mut self,
+        ty_handle: naga::Handle<naga::Type>,
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+        sizes: &dyn Fn(naga::Handle<naga::Override>) -> Option<TokenStream>,
+    ) -> Option<syn::Type> {
+        if pending_overrides(ty_handle, module)?.is_empty() {
+            self.rust_type_ident(ty_handle, module, args)
+        } else {
+            self.make_type(ty_handle, module, args, sizes)
+        }
+    }
+
+    /// Builds the Rust type of a type, where any arrays sized by overrides are given sizes by `sizes`.
+    fn make_type(
+        &mut self,
+        ty_handle: naga::Handle<naga::Type>,
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+        sizes: &dyn Fn(naga::Handle<naga::Override>) -> Option<TokenStream>,
+    ) -> Option<syn::Type> {
+        let ty = match module.types.get_handle(ty_handle) {
+            Err(_) => return None,
+            Ok(ty) => ty,
+        };
+        if let Some(ty_ident) = rust_type(&ty.inner, args) {
+            return Some(ty_ident);
+        };
+
+        match &ty.inner {
+            naga::TypeInner::Array { base, size, .. }
+            | naga::TypeInner::BindingArray { base, size } => {
+                let base_type = self.make_member_type(*base, module, args, sizes)?;
+                match size {
+                    naga::ArraySize::Constant(size) => {
+                        let size = size.get();
+                        // Keep the link to the named constant that the size came from, if we can find it
+                        let constant = self
+                            .array_sizes
+                            .constant(size)
+                            .and_then(|constant| module.constants[constant].name.as_ref())
+                            .and_then(|name| syn::parse_str::<syn::Ident>(name).ok());
+                        match constant {
+                            Some(constant) => Some(syn::parse_quote!(
+                                [#base_type; constants::#constant::VALUE as usize]
+                            )),
+                            None => Some(syn::parse_quote!([#base_type; #size as usize])),
+                        }
+                    }
+                    naga::ArraySize::Dynamic => Some(syn::parse_quote!(Vec<#base_type>)),
+                    naga::ArraySize::Pending(naga::PendingArraySize::Override(override_handle)) => {
+                        let size = sizes(*override_handle)?;
+                        Some(syn::parse_quote!([#base_type; #size]))
+                    }
+                    naga::ArraySize::Pending(naga::PendingArraySize::Expression(_)) => None,
+                }
+            }
+            naga::TypeInner::Struct { members, .. } => {
+                let params = pending_overrides(ty_handle, module)?;
+                if params.is_empty() {
+                    return self
+                        .make_struct(ty_handle, ty, members, &[], module, args)
+                        .map(|struct_name| syn::parse_quote!(#struct_name));
+                }
+
+                // Structs containing override-sized arrays are generic over their sizes
+                let (struct_name, params) = match self.generic_structs.get(&ty_handle) {
+                    Some(generic_struct) => generic_struct.clone(),
+                    None => {
+                        let struct_name =
+                            self.make_struct(ty_handle, ty, members, &params, module, args)?;
+                        self.generic_structs
+                            .insert(ty_handle, (struct_name.clone(), params.clone()));
+                        (struct_name, params)
+                    }
+                };
+                let sizes = params
+                    .iter()
+                    .map(|override_handle| sizes(*override_handle))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(syn::parse_quote!(#struct_name<#(#sizes),*>))
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds the definition of a struct, generic over the sizes given by each of the overrides in `params`.
+    fn make_struct(
+        &mut self,
+        ty_handle: naga::Handle<naga::Type>,
+        ty: &naga::Type,
+        members: &[naga::StructMember],
+        params: &[naga::Handle<naga::Override>],
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+    ) -> Option<syn::Ident> {
+        let struct_name = ty.name.as_ref();
+        let struct_name = struct_name?;
+
+        // Apply filter
+        if let Some(struct_name_filter) = &self.structs_filter {
+            if !struct_name_filter.contains(struct_name) {
+                return None;
+            }
+        }
+
+        let serde_derives: Vec<syn::Path> = if args.gen_serde {
+            vec![
+                syn::parse_quote!(serde::Serialize),
+                syn::parse_quote!(serde::Deserialize),
+            ]
+        } else {
+            vec![]
+        };
+
+        // Extra attributes requested by the user
+        let extra_attributes = args.struct_attributes.iter().chain(
+            args.struct_attributes_by_name
+                .get(struct_name)
+                .into_iter()
+                .flatten(),
+        );
+
+        let param_idents = params
+            .iter()
+            .map(|override_handle| crate::overrides::override_ident(*override_handle, module))
+            .collect::<Option<Vec<_>>>()?;
+        let param_sizes = |override_handle| {
+            let ident = crate::overrides::override_ident(override_handle, module)?;
+            Some(quote::quote!(#ident))
+        };
+
+        let member_names = struct_member_idents(members);
+        let members: Option<Vec<_>> = members
+            .iter()
+            .zip(member_names)
+            .map(|(member, member_name)| {
+                let member_ty = self.make_member_type(member.ty, module, args, &param_sizes);
+
+                let mut attributes = match &member.name {
+                    Some(member_name) => args
+                        .doc_comments
+                        .member_attributes(struct_name, member_name),
+                    None => proc_macro2::TokenStream::new(),
+                };
+                // Runtime-sized fields must be marked as such when using encase
+                if args.gen_encase {
+                    let ty = module.types.get_handle(member.ty);
+                    if let Ok(naga::Type {
+                        inner:
+                            naga::TypeInner::Array {
+                                size: naga::ArraySize::Dynamic,
+                                ..
+                            }
+                            | naga::TypeInner::BindingArray {
+                                size: naga::ArraySize::Dynamic,
+                                ..
+                            },
+                        ..
+                    }) = ty
+                    {
+                        attributes.extend(quote::quote!(#[size(runtime)]))
+                    }
+                }
+
+                member_ty.and_then(|member_ty| {
+                    member_name.map(|member_name| (member_name, member_ty, attributes, member.ty))
+                })
+            })
+            .collect();
+        let members = members?;
+
+        // Extra derives requested by the user, skipping any derives which can't hold for this struct or which are
+        // already derived. Members are built first, so that any structs they contain know what they derive
+        let mut seen_derives = HashSet::new();
+        let extra_derives: Vec<&syn::Path> = serde_derives
+            .iter()
+            .chain(args.struct_derives.iter())
+            .chain(
+                args.struct_derives_by_name
+                    .get(struct_name)
+                    .into_iter()
+                    .flatten(),
+            )
+            .filter(|derive| {
+                let derive_name = derive_name(derive);
+                // `Default` is implemented by hand if zero values are being generated
+                let already_implemented = BASE_DERIVES.contains(&derive_name.as_str())
+                    || (args.gen_default && derive_name == "Default");
+                !already_implemented
+                    && seen_derives.insert(derive_name.clone())
+                    && members.iter().all(|(_, _, _, member_ty)| {
+                        self.derive_holds(&derive_name, *member_ty, module, args)
+                    })
+            })
+            .collect();
+        let declared_at = crate::source_location(module.types.get_span(ty_handle), args)
+            .map(|location| format!("Declared at `{}`.", location));
+        let struct_docs = args
+            .doc_comments
+            .item_attributes(struct_name, declared_at.as_deref());
+        let struct_name = syn::parse_str::<syn::Ident>(struct_name).ok()?;
+
+        // The zero value of every member, for building a zero-valued instance of this struct
+        let zeroed_members = if args.gen_default {
+            members
+                .iter()
+                .map(|(member_name, member_ty, _, member_ty_handle)| {
+                    let zero = zero_value(*member_ty_handle, member_ty, module, args)?;
+                    Some(quote::quote! { #member_name: #zero })
+                })
+                .collect::<Option<Vec<_>>>()
+        } else {
+            None
+        };
+
+        let members = members
+            .into_iter()
+            .map(|(member_name, member_ty, attributes, _)| {
+                quote::quote! {
+                    #attributes
+                    pub #member_name: #member_ty
+                }
+            });
+
+        #[allow(unused_mut)]
+        let mut bonus_struct_derives = TokenStream::new();
+        if args.gen_encase {
+            bonus_struct_derives.extend(quote::quote!(encase::ShaderType,))
+        }
+
+        // Remember what was derived, so that structs containing this one know what they can derive
+        let mut derived: HashSet<String> = BASE_DERIVES.iter().map(ToString::to_string).collect();
+        derived.extend(extra_derives.iter().map(|derive| derive_name(derive)));
+        self.struct_derives.insert(ty_handle, derived);
+
+        self.definitions.push(syn::parse_quote! {
+            #struct_docs
+            #[allow(unused, non_camel_case_types, non_upper_case_globals)]
+            #[derive(Debug, PartialEq, Clone, #bonus_struct_derives #(#extra_derives ,)*)]
+            #(#extra_attributes)*
+            pub struct #struct_name<#(const #param_idents: usize),*> {
+                #(#members ,)*
+            }
+        });
+
+        // Match WGSL's zero-value semantics for defaults
+        if let Some(zeroed_members) = zeroed_members {
+            self.definitions.push(syn::parse_quote! {
+                #[allow(unused, non_upper_case_globals)]
+                impl<#(const #param_idents: usize),*> #struct_name<#(#param_idents),*> {
+                    #[doc = "Constructs an instance of this struct with every member set to its zero value, as in WGSL."]
+                    pub const fn zeroed() -> Self {
+                        Self {
+                            #(#zeroed_members ,)*
+                        }
+                    }
+                }
+            });
+            self.definitions.push(syn::parse_quote! {
+                #[allow(non_upper_case_globals)]
+                impl<#(const #param_idents: usize),*> Default for #struct_name<#(#param_idents),*> {
+                    fn default() -> Self {
+                        Self::zeroed()
+                    }
+                }
+            });
+        }
+
+        // Give generic structs an alias which uses the default sizes
+        if !params.is_empty() {
+            let default_sizes = params
+                .iter()
+                .map(|override_handle| default_array_size(*override_handle, module, args))
+                .collect::<Option<Vec<_>>>();
+            if let Some(default_sizes) = default_sizes {
+                let alias_name = quote::format_ident!("{}Default", struct_name);
+                let doc = format!(
+                    "The `{}` struct, with each array sized by the default value of the override giving its length.",
+                    struct_name
+                );
+                self.definitions.push(syn::parse_quote! {
+                    #[doc = #doc]
+                    #[allow(unused, non_camel_case_types)]
+                    pub type #alias_name = #struct_name<#(#default_sizes),*>;
+                });
+            }
+        }
+
+        Some(struct_name)
+    }
+
+    /// Takes a handle to a type, and a module where the type resides, and tries to return an identifier
+    /// of that type, in Rust. Note that for structs this will be an identifier in to the set of structs generated
+    /// by calling `TypesDefinitions::definitions()`, so your output should make sure to include everything from
+    /// there in the scope where the returned identifier is used.
+    pub fn rust_type_ident(
+        &mut self,
+        ty_handle: naga::Handle<naga::Type>,
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+    ) -> Option<syn::Type> {
+        if let Some(ident) = self.references.get(&ty_handle).cloned() {
+            return Some(ident);
+        }
+
+        if let Some(built) = self.try_make_type(ty_handle, module, args) {
+            self.references.insert(ty_handle, built.clone());
+            return Some(built);
+        }
+
+        None
+    }
+
+    /// Warns about every array length which is written as a literal even though it matches the value of a named
+    /// constant, when it can't be told whether the length was written as that constant in the source.
+    pub(crate) fn report_array_sizes(
+        &self,
+        module: &naga::Module,
+        args: &ModuleToTokensConfig,
+        diagnostics: &mut Diagnostics,
+    ) {
+        let mut sizes = Vec::new();
+        for (_, ty) in module.types.iter() {
+            if let naga::TypeInner::Array {
+                size: naga::ArraySize::Constant(size),
+                ..
+            }
+            | naga::TypeInner::BindingArray {
+                size: naga::ArraySize::Constant(size),
+                ..
+            } = ty.inner
+            {
+                if !sizes.contains(&size.get()) {
+                    sizes.push(size.get());
+                }
+            }
+        }
+
+        for size in sizes {
+            let Some(reason) = self.array_sizes.fallback_reason(size) else {
+                continue;
+            };
+            for (handle, constant) in module.constants.iter() {
+                let Some(name) = &constant.name else {
+                    continue;
+                };
+                if constant_u32(constant, module) != Some(size) {
+                    continue;
+                }
+                let start = diagnostics.len();
+                diagnostics.warn(format!(
+                    "arrays of length {} are written with a literal length rather than the constant `{}`, as {}",
+                    size, name, reason
+                ));
+                diagnostics.locate_since(
+                    start,
+                    crate::source_location(module.constants.get_span(handle), args),
+                );
+            }
+        }
+    }
+
+    /// Gives the set of definitions required by the identifiers generated by this object. These should be
+    /// emitted somewhere accessible by the places that the identifiers were used.
+    pub fn definitions(self) -> Vec<syn::Item> {
+        self.definitions
+    }
+}
@@ -0,0 +1,135 @@
+#![deny(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+use naga_to_tokenstream::{ModuleSource, ModuleToTokens, ModuleToTokensConfig};
+
+/// The arguments given to `include_shader!`: a path, followed by the names of the generation options to enable.
+struct IncludeShaderArgs {
+    path: syn::LitStr,
+    options: Vec<syn::Ident>,
+}
+
+impl syn::parse::Parse for IncludeShaderArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+
+        let mut options = Vec::new();
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            options.push(input.parse()?);
+        }
+
+        Ok(Self { path, options })
+    }
+}
+
+impl IncludeShaderArgs {
+    /// Builds the configuration described by the options given, erroring on any options we don't recognise.
+    fn config(&self) -> syn::Result<ModuleToTokensConfig> {
+        let mut cfg = ModuleToTokensConfig::default();
+        for option in &self.options {
+            match option.to_string().as_str() {
+                "glam" => cfg.gen_glam = true,
+                "encase" => cfg.gen_encase = true,
+                "naga" => cfg.gen_naga = true,
+                "wgpu" => cfg.gen_wgpu = true,
+                "default" => cfg.gen_default = true,
+                "serde" => cfg.gen_serde = true,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        option,
+                        format!(
+                            "unknown option `{}`, expected one of `glam`, `encase`, `naga`, `wgpu`, `default` or `serde`",
+                            other
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(cfg)
+    }
+}
+
+/// Reads and parses the shader at the path given, and converts it to Rust definitions, validating it along the way.
+fn include_shader_impl(args: IncludeShaderArgs) -> syn::Result<proc_macro2::TokenStream> {
+    let mut cfg = args.config()?;
+
+    // Paths are relative to the root of the crate using the macro, rather than to the file invoking it
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        syn::Error::new_spanned(
+            &args.path,
+            "`CARGO_MANIFEST_DIR` is not set, so the shader can't be found",
+        )
+    })?;
+    let relative_path = args.path.value();
+    let path = std::path::Path::new(&manifest_dir).join(&relative_path);
+
+    let source = std::fs::read_to_string(&path).map_err(|error| {
+        syn::Error::new_spanned(
+            &args.path,
+            format!("failed to read shader `{}`: {}", path.display(), error),
+        )
+    })?;
+
+    let module = naga::front::wgsl::parse_str(&source).map_err(|error| {
+        syn::Error::new_spanned(
+            &args.path,
+            error.emit_to_string_with_path(&source, &relative_path),
+        )
+    })?;
+
+    cfg.source = Some(ModuleSource {
+        code: source,
+        path: Some(relative_path.into()),
+        embed_verbatim: false,
+    });
+    // The module is validated while converting it, with the options in `cfg`
+    let (items, diagnostics) = module.to_items_with_diagnostics(cfg);
+
+    // Warnings can't be emitted from a stable proc macro, but errors mean the output can't be relied upon
+    let mut errors = diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            diagnostic.severity == naga_to_tokenstream::diagnostics::Severity::Error
+        })
+        .map(|diagnostic| syn::Error::new_spanned(&args.path, diagnostic));
+    if let Some(mut error) = errors.next() {
+        error.extend(errors);
+        return Err(error);
+    }
+
+    // Reading the shader through `include_str!` tells the compiler to rebuild when the shader changes
+    let path = path.to_string_lossy();
+    let mut tokens = quote::quote! {
+        const _: &str = include_str!(#path);
+    };
+    tokens.extend(items.into_iter().map(quote::ToTokens::into_token_stream));
+    Ok(tokens)
+}
+
+/// Generates Rust definitions for the types, constants, overrides, globals and entry points of a WGSL shader, at
+/// compile time.
+///
+/// The first argument is the path to the shader, relative to the directory containing the crate's `Cargo.toml`.
+/// It can be followed by any of `glam`, `encase`, `naga`, `wgpu`, `default` and `serde`, enabling the matching
+/// options of `naga_to_tokenstream::ModuleToTokensConfig`. Parsing and validation errors are reported as
+/// compile errors. This macro is also re-exported as `naga_to_tokenstream::include_shader!` by the `macros` feature
+/// of `naga-to-tokenstream`.
+///
+/// # Usage
+///
+/// ```ignore
+/// mod shader {
+///     naga_to_tokenstream_macros::include_shader!("shaders/compute.wgsl", glam, encase);
+/// }
+/// ```
+#[proc_macro]
+pub fn include_shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = syn::parse_macro_input!(input as IncludeShaderArgs);
+    include_shader_impl(args)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,99 @@
+mod particles {
+    naga_to_tokenstream_macros::include_shader!("tests/shaders/particles.wgsl", default);
+}
+
+#[test]
+fn includes_shader() {
+    assert_eq!(particles::constants::WORKGROUP_SIZE::VALUE, 64);
+    assert_eq!(particles::entry_points::update::NAME, "update");
+    assert_eq!(particles::entry_points::update::WORKGROUP_SIZE, [64, 1, 1]);
+    assert_eq!(particles::globals::particles::NAME, "particles");
+    assert_eq!(
+        particles::entry_points::update::globals::particles::binding::BINDING,
+        0
+    );
+
+    let particle = particles::types::Particle::default();
+    assert_eq!(particle.position, [0.0, 0.0]);
+    assert_eq!(particle.velocity, [0.0, 0.0]);
+}
+
+mod blocks {
+    naga_to_tokenstream_macros::include_shader!("tests/shaders/blocks.wgsl", default);
+}
+
+#[test]
+fn dispatches_with_overrides() {
+    use blocks::{entry_points, overrides::Overrides};
+
+    let overrides = Overrides::default();
+    assert_eq!(entry_points::fill::workgroup_size(&overrides), [64, 1, 1]);
+    assert_eq!(
+        entry_points::fill::dispatch_size(&overrides, [100, 3, 1]),
+        [2, 3, 1]
+    );
+
+    let overrides = Overrides {
+        BLOCK_SIZE: Some(16),
+        ROWS: Some(2),
+    };
+    assert_eq!(
+        entry_points::fill::dispatch_size(&overrides, [100, 3, 1]),
+        [7, 2, 1]
+    );
+    assert_eq!(
+        entry_points::scaled::dispatch_size(&overrides, [100, 1, 1]),
+        Some([4, 1, 1])
+    );
+
+    // Overflowing the workgroup size fails to evaluate it
+    let overrides = Overrides {
+        BLOCK_SIZE: Some(u32::MAX),
+        ROWS: None,
+    };
+    assert_eq!(entry_points::scaled::workgroup_size(&overrides), None);
+    assert_eq!(
+        entry_points::scaled::dispatch_size(&overrides, [100, 1, 1]),
+        None
+    );
+}
+
+mod requirements {
+    naga_to_tokenstream_macros::include_shader!("tests/shaders/requirements.wgsl", naga, wgpu);
+}
+
+#[test]
+fn required_features_compile_against_wgpu() {
+    use requirements::entry_points;
+
+    assert_eq!(
+        entry_points::scaled::REQUIRED_FEATURES,
+        wgpu::Features::PUSH_CONSTANTS | wgpu::Features::SHADER_F64
+    );
+    assert_eq!(
+        entry_points::smallest::REQUIRED_FEATURES,
+        wgpu::Features::SHADER_INT64 | wgpu::Features::SHADER_INT64_ATOMIC_MIN_MAX
+    );
+    assert_eq!(
+        entry_points::clear::REQUIRED_FEATURES,
+        wgpu::Features::TEXTURE_FORMAT_16BIT_NORM
+            | wgpu::Features::BGRA8UNORM_STORAGE
+            | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+    );
+    assert_eq!(
+        entry_points::fragment::REQUIRED_FEATURES,
+        wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+            | wgpu::Features::TEXTURE_BINDING_ARRAY
+            | wgpu::Features::BUFFER_BINDING_ARRAY
+            | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY
+    );
+
+    // The module needs everything its entry points need
+    assert_eq!(
+        requirements::REQUIRED_FEATURES,
+        entry_points::scaled::REQUIRED_FEATURES
+            | entry_points::smallest::REQUIRED_FEATURES
+            | entry_points::clear::REQUIRED_FEATURES
+            | entry_points::fragment::REQUIRED_FEATURES
+    );
+}
@@ -0,0 +1,10 @@
+override BLOCK_SIZE: u32 = 64u;
+override ROWS: u32 = 1u;
+
+@compute
+@workgroup_size(BLOCK_SIZE, ROWS)
+fn fill() {}
+
+@compute
+@workgroup_size(BLOCK_SIZE * 2u)
+fn scaled() {}
@@ -0,0 +1,17 @@
+/// The number of particles updated by each workgroup.
+const WORKGROUP_SIZE: u32 = 64u;
+
+/// A single simulated particle.
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> particles: array<Particle>;
+
+@compute
+@workgroup_size(WORKGROUP_SIZE)
+fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+    particles[id.x].position += particles[id.x].velocity;
+}
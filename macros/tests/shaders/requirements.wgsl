@@ -0,0 +1,40 @@
+struct PushConstants {
+    scale: f32,
+}
+
+struct Buffer {
+    data: array<f32>,
+}
+
+var<push_constant> push: PushConstants;
+@group(0) @binding(0) var<storage, read_write> values: array<f64>;
+@group(0) @binding(1) var<storage, read_write> minimum: atomic<u64>;
+@group(0) @binding(2) var output: texture_storage_2d<bgra8unorm, write>;
+@group(0) @binding(3) var normals: texture_storage_2d<rg16snorm, read_write>;
+@group(0) @binding(4) var textures: binding_array<texture_2d<f32>, 4>;
+@group(0) @binding(5) var linear: sampler;
+@group(0) @binding(6) var<storage> buffers: binding_array<Buffer, 4>;
+
+@compute
+@workgroup_size(64)
+fn scaled(@builtin(global_invocation_id) id: vec3<u32>) {
+    values[id.x] = values[id.x] * f64(push.scale);
+}
+
+@compute
+@workgroup_size(1)
+fn smallest() {
+    atomicMin(&minimum, 1lu);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn clear(@builtin(global_invocation_id) id: vec3<u32>) {
+    textureStore(output, id.xy, vec4(0.0));
+    textureStore(normals, id.xy, textureLoad(normals, id.xy));
+}
+
+@fragment
+fn fragment(@location(0) @interpolate(flat) index: u32) -> @location(0) vec4<f32> {
+    return textureSample(textures[index], linear, vec2(buffers[index].data[0]));
+}
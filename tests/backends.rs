@@ -0,0 +1,190 @@
+#![cfg(all(
+    feature = "spv-out",
+    feature = "glsl-out",
+    feature = "hlsl-out",
+    feature = "msl-out"
+))]
+
+use naga_to_tokenstream::{ModuleToTokens, ModuleToTokensConfig};
+
+const SHADER: &str = r#"
+override SCALE: f32 = 2.0;
+
+@group(0) @binding(0) var<storage, read_write> values: array<f32>;
+
+@compute
+@workgroup_size(64)
+fn double(@builtin(global_invocation_id) id: vec3<u32>) {
+    values[id.x] *= SCALE;
+}
+
+@compute
+@workgroup_size(64)
+fn clear(@builtin(global_invocation_id) id: vec3<u32>) {
+    values[id.x] = 0.0;
+}
+"#;
+
+/// Gives the names of the constants within a collection of items.
+fn constant_names(items: &[syn::Item]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Const(constant) => Some(constant.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Gives the items within the module with the given name.
+fn module_items<'a>(items: &'a [syn::Item], name: &str) -> &'a [syn::Item] {
+    items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Mod(module) if module.ident == name => {
+                Some(module.content.as_ref().unwrap().1.as_slice())
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn embeds_other_shader_languages() {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let (items, diagnostics) = module.to_items_with_diagnostics(ModuleToTokensConfig {
+        gen_spirv: true,
+        gen_glsl: true,
+        gen_hlsl: true,
+        gen_msl: true,
+        ..Default::default()
+    });
+    // Naga can't yet write overrides as WGSL, but the other languages are given their default values
+    assert!(
+        diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.message.contains("as WGSL")),
+        "{:?}",
+        diagnostics
+    );
+
+    // GLSL can only be written for single entry points
+    let module_constants = constant_names(&items);
+    for name in ["SPIRV", "HLSL", "MSL"] {
+        assert!(
+            module_constants.iter().any(|constant| constant == name),
+            "the module has no `{}`",
+            name
+        );
+    }
+    assert!(!module_constants.iter().any(|constant| constant == "GLSL"));
+
+    let entry_points = module_items(&items, "entry_points");
+    for entry_point in ["double", "clear"] {
+        let entry_point_constants = constant_names(module_items(entry_points, entry_point));
+        for name in ["SPIRV", "GLSL", "HLSL", "MSL"] {
+            assert!(
+                entry_point_constants
+                    .iter()
+                    .any(|constant| constant == name),
+                "`{}` has no `{}`",
+                entry_point,
+                name
+            );
+        }
+    }
+}
+
+/// Gives the words of the `SPIRV` constant within a collection of items.
+fn spirv_words(items: &[syn::Item]) -> Vec<u32> {
+    let spirv = items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Const(constant) if constant.ident == "SPIRV" => Some(&constant.expr),
+            _ => None,
+        })
+        .unwrap();
+    let syn::Expr::Reference(syn::ExprReference { expr, .. }) = &**spirv else {
+        panic!("`SPIRV` isn't a reference to an array");
+    };
+    let syn::Expr::Array(words) = &**expr else {
+        panic!("`SPIRV` isn't a reference to an array");
+    };
+    words
+        .elems
+        .iter()
+        .map(|word| match word {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(word),
+                ..
+            }) => word.base10_parse().unwrap(),
+            _ => panic!("`SPIRV` contains a non-literal word"),
+        })
+        .collect()
+}
+
+#[test]
+fn spirv_round_trips() {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let items = module.to_items(ModuleToTokensConfig {
+        gen_spirv: true,
+        ..Default::default()
+    });
+    let words = spirv_words(&items);
+
+    // The SPIR-V magic number, followed by the version 1.0
+    assert_eq!(words[0], 0x07230203);
+    assert_eq!(words[1], 0x00010000);
+}
+
+#[test]
+fn spirv_options_are_used() {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let plain = spirv_words(&module.to_items(ModuleToTokensConfig {
+        gen_spirv: true,
+        ..Default::default()
+    }));
+    let configured = spirv_words(&module.to_items(ModuleToTokensConfig {
+        gen_spirv: true,
+        spirv_options: naga_to_tokenstream::SpirvOptions {
+            lang_version: (1, 3),
+            debug_info: true,
+            ..Default::default()
+        },
+        source: Some(naga_to_tokenstream::ModuleSource {
+            code: SHADER.to_owned(),
+            path: Some("shader.wgsl".into()),
+            embed_verbatim: false,
+        }),
+        ..Default::default()
+    }));
+
+    assert_eq!(configured[1], 0x00010300);
+    // Debug information includes the names of variables, as well as the source code
+    assert!(configured.len() > plain.len());
+}
+
+#[test]
+fn spirv_shader_module_descriptors() {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let items = module.to_items(ModuleToTokensConfig {
+        gen_spirv: true,
+        gen_wgpu: true,
+        ..Default::default()
+    });
+
+    let has_descriptor = |items: &[syn::Item]| {
+        items.iter().any(|item| {
+            matches!(item, syn::Item::Fn(function) if function.sig.ident == "spirv_shader_module_descriptor")
+        })
+    };
+    assert!(has_descriptor(&items));
+    let entry_points = module_items(&items, "entry_points");
+    for entry_point in ["double", "clear"] {
+        assert!(
+            has_descriptor(module_items(entry_points, entry_point)),
+            "`{}` has no descriptor helper",
+            entry_point
+        );
+    }
+}
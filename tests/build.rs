@@ -0,0 +1,104 @@
+#[test]
+fn build_snapshot() {
+    let dir =
+        std::env::temp_dir().join(format!("naga-to-tokenstream-build-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let shader = dir.join("blur.wgsl");
+    std::fs::write(
+        &shader,
+        r#"
+    /// The radius of the blur, in pixels.
+    const RADIUS: i32 = 4;
+
+    @group(0) @binding(0) var input: texture_2d<f32>;
+    @group(0) @binding(1) var output: texture_storage_2d<rgba8unorm, write>;
+
+    @compute
+    @workgroup_size(8, 8)
+    fn blur(@builtin(global_invocation_id) id: vec3<u32>) {
+        var total = vec4<f32>(0.0);
+        for (var i = -RADIUS; i <= RADIUS; i++) {
+            total += textureLoad(input, vec2<i32>(id.xy) + vec2<i32>(i, 0), 0);
+        }
+        textureStore(output, id.xy, total / f32(2 * RADIUS + 1));
+    }
+    "#,
+    )
+    .unwrap();
+
+    let outputs = naga_to_tokenstream::build::Builder::new()
+        .shader(&shader)
+        .config(naga_to_tokenstream::ModuleToTokensConfig {
+            // Keep the snapshot the same whether or not the `minify` feature is enabled
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        })
+        .out_dir(&dir)
+        .build()
+        .unwrap();
+    assert_eq!(outputs, [dir.join("blur.rs")]);
+
+    let generated = std::fs::read_to_string(&outputs[0]).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // The source location depends on the temporary directory used
+    insta::assert_snapshot!(generated.replace(&*dir.to_string_lossy(), "$DIR"));
+}
+
+#[test]
+fn duplicate_outputs() {
+    let dir = std::env::temp_dir().join(format!(
+        "naga-to-tokenstream-build-duplicate-{}",
+        std::process::id()
+    ));
+
+    // Outputs are checked before any shader is read, so the shaders needn't exist
+    let error = naga_to_tokenstream::build::Builder::new()
+        .shaders(["a/blur.wgsl", "shader.vert", "shader.frag", "b/blur.wgsl"])
+        .out_dir(&dir)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "`a/blur.wgsl` and `b/blur.wgsl` would both be written to `{}`",
+            dir.join("blur.rs").display()
+        )
+    );
+    assert!(!dir.exists());
+}
+
+#[test]
+fn validation_errors() {
+    let dir = std::env::temp_dir().join(format!(
+        "naga-to-tokenstream-build-validation-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Runtime-sized arrays can't be uniform, which is only caught by validation
+    let shader = dir.join("values.wgsl");
+    std::fs::write(
+        &shader,
+        "@group(0) @binding(0) var<uniform> values: array<f32>;\n",
+    )
+    .unwrap();
+
+    let error = naga_to_tokenstream::build::Builder::new()
+        .shader(&shader)
+        .out_dir(&dir)
+        .build()
+        .unwrap_err();
+    let written = dir.join("values.rs").exists();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(matches!(
+        &error,
+        naga_to_tokenstream::build::BuildError::Validation { path, .. } if *path == shader
+    ));
+    let message = error.to_string();
+    assert!(message.starts_with(&format!("failed to validate `{}`:\n", shader.display())));
+    assert!(message.contains("values.wgsl:1:23"));
+    assert!(!written);
+}
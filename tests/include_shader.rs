@@ -0,0 +1,11 @@
+#![cfg(feature = "macros")]
+
+mod particles {
+    naga_to_tokenstream::include_shader!("macros/tests/shaders/particles.wgsl", default);
+}
+
+#[test]
+fn includes_shader_through_reexport() {
+    assert_eq!(particles::constants::WORKGROUP_SIZE::VALUE, 64);
+    assert_eq!(particles::entry_points::update::WORKGROUP_SIZE, [64, 1, 1]);
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "minify")]
+
+use naga_to_tokenstream::{MinifyLevel, ModuleToTokens, ModuleToTokensConfig};
+
+// Short names clash with those given to identifiers by minification. Overrides are left out, since naga can't yet
+// write them as WGSL.
+const SHADER: &str = r#"
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+}
+
+@group(0) @binding(0) var<storage, read_write> A: array<Particle>;
+@group(0) @binding(1) var<uniform> a: f32;
+var<private> b: f32;
+
+fn B(particle: Particle) -> Particle {
+    var moved = particle;
+    moved.position += moved.velocity * a;
+    return moved;
+}
+
+@compute
+@workgroup_size(64)
+fn C(@builtin(global_invocation_id) id: vec3<u32>) {
+    A[id.x] = B(A[id.x]);
+}
+
+@compute
+@workgroup_size(64)
+fn c(@builtin(global_invocation_id) id: vec3<u32>) {
+    b = a;
+    A[id.x].velocity *= b;
+}
+"#;
+
+/// Gives the value of the string constant with the given name within a collection of items.
+fn string_constant(items: &[syn::Item], name: &str) -> String {
+    items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Const(constant) if constant.ident == name => match &*constant.expr {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no `{}` string constant", name))
+}
+
+/// Gives the items within each module in a collection of items, by module name.
+fn modules(items: &[syn::Item]) -> Vec<(String, &[syn::Item])> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Mod(module) => Some((
+                module.ident.to_string(),
+                module.content.as_ref()?.1.as_slice(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Gives the items within the module with the given name.
+fn module_items<'a>(items: &'a [syn::Item], name: &str) -> &'a [syn::Item] {
+    modules(items)
+        .into_iter()
+        .find_map(|(module, items)| (module == name).then_some(items))
+        .unwrap_or_else(|| panic!("no `{}` module", name))
+}
+
+fn generate(minify: MinifyLevel) -> Vec<syn::Item> {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let (items, diagnostics) = module.to_items_with_diagnostics(ModuleToTokensConfig {
+        minify,
+        ..Default::default()
+    });
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    items
+}
+
+#[test]
+fn minified_sources_keep_generated_names() {
+    let items = generate(MinifyLevel::Full);
+
+    let source = string_constant(&items, "SOURCE");
+    let minified = naga::front::wgsl::parse_str(&source).unwrap();
+    let global_names = |module: &naga::Module| -> Vec<String> {
+        module
+            .global_variables
+            .iter()
+            .filter(|(_, global)| global.binding.is_some())
+            .filter_map(|(_, global)| global.name.clone())
+            .collect()
+    };
+    let entry_point_names = |module: &naga::Module| -> Vec<String> {
+        module
+            .entry_points
+            .iter()
+            .map(|entry_point| entry_point.name.clone())
+            .collect()
+    };
+
+    // The struct and function names are shortened
+    assert!(!source.contains("Particle"), "{}", source);
+    assert!(!source.contains("moved"), "{}", source);
+
+    let globals = modules(module_items(&items, "globals"));
+    assert_eq!(globals.len(), 3);
+    for (_, global) in &globals {
+        if global
+            .iter()
+            .any(|item| matches!(item, syn::Item::Mod(module) if module.ident == "binding"))
+        {
+            let name = string_constant(global, "NAME");
+            assert!(
+                global_names(&minified).contains(&name),
+                "`{}` was renamed in {}",
+                name,
+                source
+            );
+        }
+    }
+
+    let entry_points = modules(module_items(&items, "entry_points"));
+    assert_eq!(entry_points.len(), 2);
+    for (_, entry_point) in &entry_points {
+        let name = string_constant(entry_point, "NAME");
+        assert!(
+            entry_point_names(&minified).contains(&name),
+            "`{}` was renamed in {}",
+            name,
+            source
+        );
+
+        // Each exclusive source keeps the names of the entry point and the bound globals it uses
+        let exclusive_source = string_constant(entry_point, "EXCLUSIVE_SOURCE");
+        let exclusive = naga::front::wgsl::parse_str(&exclusive_source).unwrap();
+        assert_eq!(entry_point_names(&exclusive), [name]);
+        for (_, global) in modules(module_items(entry_point, "globals")) {
+            if global
+                .iter()
+                .any(|item| matches!(item, syn::Item::Mod(module) if module.ident == "binding"))
+            {
+                let name = string_constant(global, "NAME");
+                assert!(
+                    global_names(&exclusive).contains(&name),
+                    "`{}` was renamed in {}",
+                    name,
+                    exclusive_source
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn whitespace_minification_keeps_identifiers() {
+    let source = string_constant(&generate(MinifyLevel::Whitespace), "SOURCE");
+    assert!(!source.contains('\n'), "{}", source);
+    for identifier in [
+        "struct Particle",
+        "moved",
+        "fn B",
+        "var<private>b",
+        "fn C",
+        "fn c",
+    ] {
+        assert!(
+            source.contains(identifier),
+            "`{}` is missing from {}",
+            identifier,
+            source
+        );
+    }
+    naga::front::wgsl::parse_str(&source).unwrap();
+}
+
+#[test]
+fn sources_can_be_left_unminified() {
+    let source = string_constant(&generate(MinifyLevel::None), "SOURCE");
+    assert!(source.contains("struct Particle {\n"), "{}", source);
+}
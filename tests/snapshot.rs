@@ -29,10 +29,1070 @@ fn readme_snapshot() {
     let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
         &src,
         naga_to_tokenstream::ModuleToTokensConfig {
-            structs_filter: None,
             gen_glam: true,
             gen_encase: true,
             gen_naga: true,
+            gen_wgpu: true,
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn override_workgroup_size_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    override BLOCK_SIZE: u32 = 64u;
+    @id(7) override ROWS: u32;
+    override SCALE: f32 = 0.5;
+    override DEPTH: u32 = BLOCK_SIZE / 32u;
+
+    @compute
+    @workgroup_size(BLOCK_SIZE * 2u, ROWS, DEPTH)
+    fn main() {
+
+    }
+
+    @compute
+    @workgroup_size(BLOCK_SIZE, ROWS)
+    fn unchecked() {
+
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            gen_wgpu: true,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn overrides_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    @id(0) override ENABLED: bool = true;
+    override SCALE: f32 = 0.5;
+    override HALF_SCALE = SCALE / 2.0;
+    override COUNT: i32;
+
+    @compute
+    @workgroup_size(1)
+    fn main() {
+        _ = ENABLED;
+        _ = HALF_SCALE;
+        _ = COUNT;
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn unrepresentable_overrides() {
+    // WGSL can't yet declare `f16` overrides, but other front ends can
+    let mut module = naga::Module::default();
+    let span = naga::Span::UNDEFINED;
+    let f16_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar {
+                kind: naga::ScalarKind::Float,
+                width: 2,
+            }),
+        },
+        span,
+    );
+    module.overrides.append(
+        naga::Override {
+            name: Some(String::from("HALF")),
+            id: None,
+            ty: f16_ty,
+            init: None,
+        },
+        span,
+    );
+
+    let cfg = naga_to_tokenstream::ModuleToTokensConfig {
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let mut types = naga_to_tokenstream::types::TypesDefinitions::new(&module, None, &cfg);
+    let mut diagnostics = naga_to_tokenstream::diagnostics::Diagnostics::new();
+    let items =
+        naga_to_tokenstream::overrides::make_overrides(&module, &mut types, &cfg, &mut diagnostics);
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["the type of the `HALF` override has no Rust equivalent, so it was left out of `Overrides` and `to_constants`, and must be given to pipelines by hand"]
+    );
+    let tokens = quote::quote!(#(#items)*).to_string();
+    assert!(tokens.contains("pub struct Overrides { }"));
+}
+
+#[test]
+fn constants_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    const UP = vec3(0.0, 1.0, 0.0);
+    const N = 4u * 32u;
+    const ORIGIN = vec2<f32>();
+    const ONES = vec4(1);
+    const FLAGS = vec2(true, false);
+
+    const IDENTITY = mat2x2<f32>(1.0, 0.0, 0.0, 1.0);
+    const WEIGHTS = array<f32, 3>(0.25, 0.5, 0.25);
+
+    struct Light {
+        colour: vec3<f32>,
+        intensity: f32,
+        offsets: array<vec2<i32>, 2>,
+    }
+    const DEFAULT_LIGHT = Light(vec3(1.0), 2.0, array(vec2(0, 1), vec2(1, 0)));
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            gen_glam: true,
+            gen_default: true,
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn unevaluated_constants_snapshot() {
+    // The WGSL frontend folds constant expressions itself, but other frontends may not.
+    let mut module = naga::Module::default();
+    let span = naga::Span::UNDEFINED;
+    let f32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::F32),
+        },
+        span,
+    );
+    let u32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+        },
+        span,
+    );
+    let vec2_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Vector {
+                size: naga::VectorSize::Bi,
+                scalar: naga::Scalar::F32,
+            },
+        },
+        span,
+    );
+
+    let exprs = &mut module.global_expressions;
+    let four = exprs.append(naga::Expression::Literal(naga::Literal::U32(4)), span);
+    let thirty_two = exprs.append(naga::Expression::Literal(naga::Literal::U32(32)), span);
+    let product = exprs.append(
+        naga::Expression::Binary {
+            op: naga::BinaryOperator::Multiply,
+            left: four,
+            right: thirty_two,
+        },
+        span,
+    );
+    let as_float = exprs.append(
+        naga::Expression::As {
+            expr: product,
+            kind: naga::ScalarKind::Float,
+            convert: Some(4),
+        },
+        span,
+    );
+    let root = exprs.append(
+        naga::Expression::Math {
+            fun: naga::MathFunction::Sqrt,
+            arg: as_float,
+            arg1: None,
+            arg2: None,
+            arg3: None,
+        },
+        span,
+    );
+    let splat = exprs.append(
+        naga::Expression::Splat {
+            size: naga::VectorSize::Bi,
+            value: root,
+        },
+        span,
+    );
+    let negated = exprs.append(
+        naga::Expression::Unary {
+            op: naga::UnaryOperator::Negate,
+            expr: splat,
+        },
+        span,
+    );
+    let y = exprs.append(
+        naga::Expression::AccessIndex {
+            base: negated,
+            index: 1,
+        },
+        span,
+    );
+
+    for (name, ty, init) in [
+        ("PRODUCT", u32_ty, product),
+        ("ROOT", f32_ty, root),
+        ("NEGATED", vec2_ty, negated),
+        ("Y", f32_ty, y),
+    ] {
+        module.constants.append(
+            naga::Constant {
+                name: Some(name.to_owned()),
+                ty,
+                init,
+            },
+            span,
+        );
+    }
+
+    // Naga's WGSL backend can't write unevaluated constants, so we only generate the constants here.
+    let cfg = naga_to_tokenstream::ModuleToTokensConfig {
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let mut types = naga_to_tokenstream::types::TypesDefinitions::new(&module, None, &cfg);
+    let mut diagnostics = naga_to_tokenstream::diagnostics::Diagnostics::new();
+    let items =
+        naga_to_tokenstream::constants::make_constants(&module, &mut types, &cfg, &mut diagnostics);
+    assert!(diagnostics.is_empty());
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: vec![],
+        items,
+    }));
+}
+
+#[test]
+fn abstract_constants_snapshot() {
+    let mut module = naga::Module::default();
+    let span = naga::Span::UNDEFINED;
+    let f32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::F32),
+        },
+        span,
+    );
+    let u32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+        },
+        span,
+    );
+
+    for (name, ty, literal) in [
+        ("COUNT", u32_ty, naga::Literal::AbstractInt(128)),
+        ("SEVEN", f32_ty, naga::Literal::AbstractInt(7)),
+        ("HALF", f32_ty, naga::Literal::AbstractFloat(0.5)),
+        ("TOO_BIG", u32_ty, naga::Literal::AbstractInt(5_000_000_000)),
+        ("NEGATIVE", u32_ty, naga::Literal::AbstractInt(-1)),
+    ] {
+        let init = module
+            .global_expressions
+            .append(naga::Expression::Literal(literal), span);
+        module.constants.append(
+            naga::Constant {
+                name: Some(name.to_owned()),
+                ty,
+                init,
+            },
+            span,
+        );
+    }
+
+    let cfg = naga_to_tokenstream::ModuleToTokensConfig {
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let mut types = naga_to_tokenstream::types::TypesDefinitions::new(&module, None, &cfg);
+    let mut diagnostics = naga_to_tokenstream::diagnostics::Diagnostics::new();
+    let items =
+        naga_to_tokenstream::constants::make_constants(&module, &mut types, &cfg, &mut diagnostics);
+
+    let messages: Vec<_> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "warning: the value `5000000000` of the constant `TOO_BIG` can't be represented as a `u32`, so no `VALUE` was generated",
+            "warning: the value `-1` of the constant `NEGATIVE` can't be represented as a `u32`, so no `VALUE` was generated",
+        ]
+    );
+    insta::assert_snapshot!(prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: vec![],
+        items,
+    }));
+}
+
+#[test]
+fn override_sized_arrays_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    override BLOCK_SIZE: u32 = 64u;
+    override TILE: u32;
+
+    struct Block {
+        values: array<f32, BLOCK_SIZE>,
+        flags: array<u32, 4>,
+    }
+    struct Tile {
+        blocks: array<Block, TILE>,
+    }
+
+    var<workgroup> block: Block;
+    var<workgroup> scratch: array<f32, BLOCK_SIZE>;
+    var<workgroup> tile: Tile;
+
+    @compute
+    @workgroup_size(1)
+    fn main() {
+        block.values[0] = scratch[0];
+        tile.blocks[0].flags[0] = 1u;
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            gen_default: true,
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn struct_derives_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    struct Counts {
+        hits: u32,
+        misses: vec2<u32>,
+    }
+    struct Sample {
+        weight: f32,
+        counts: Counts,
+    }
+    struct History {
+        total: Counts,
+        entries: array<Counts>,
+    }
+
+    @group(0) @binding(0) var<storage> sample: Sample;
+    @group(0) @binding(1) var<storage, read_write> history: History;
+
+    @compute
+    @workgroup_size(1)
+    fn main() {
+        history.total = sample.counts;
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            gen_glam: true,
+            struct_derives: vec![
+                syn::parse_quote!(Copy),
+                syn::parse_quote!(Eq),
+                syn::parse_quote!(Hash),
+            ],
+            struct_derives_by_name: std::collections::HashMap::from([(
+                String::from("Sample"),
+                vec![syn::parse_quote!(PartialOrd)],
+            )]),
+            struct_attributes: vec![syn::parse_quote!(#[repr(C)])],
+            struct_attributes_by_name: std::collections::HashMap::from([(
+                String::from("History"),
+                vec![syn::parse_quote!(#[doc = "The history of all samples."])],
+            )]),
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn struct_derives_independent_of_type_order() {
+    let span = naga::Span::UNDEFINED;
+    let u32_ty = naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+    };
+    let struct_ty = |name: &str, ty| naga::Type {
+        name: Some(name.to_owned()),
+        inner: naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: Some(String::from("value")),
+                ty,
+                binding: None,
+                offset: 0,
+            }],
+            span: 4,
+        },
+    };
+
+    // Other front ends may declare a struct before the structs it contains. Handles are indices into the arena, so
+    // a handle to `Inner` can be made ahead of time by inserting it into a scratch arena at the same position.
+    let mut scratch = naga::UniqueArena::new();
+    let scalar = scratch.insert(u32_ty.clone(), span);
+    scratch.insert(struct_ty("Placeholder", scalar), span);
+    let inner = scratch.insert(struct_ty("Inner", scalar), span);
+
+    let mut module = naga::Module::default();
+    assert_eq!(module.types.insert(u32_ty, span), scalar);
+    module.types.insert(struct_ty("Outer", inner), span);
+    assert_eq!(module.types.insert(struct_ty("Inner", scalar), span), inner);
+
+    let cfg = naga_to_tokenstream::ModuleToTokensConfig {
+        gen_serde: true,
+        struct_derives: vec![
+            syn::parse_quote!(Copy),
+            syn::parse_quote!(Hash),
+            syn::parse_quote!(serde::Serialize),
+        ],
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let items =
+        naga_to_tokenstream::types::TypesDefinitions::new(&module, None, &cfg).definitions();
+
+    for name in ["Outer", "Inner"] {
+        let derives = items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Struct(item) if item.ident == name => Some(item),
+                _ => None,
+            })
+            .unwrap()
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("derive"))
+            .unwrap()
+            .parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|path| quote::ToTokens::to_token_stream(&path).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            derives,
+            [
+                "Debug",
+                "PartialEq",
+                "Clone",
+                "serde :: Serialize",
+                "serde :: Deserialize",
+                "Copy",
+                "Hash"
+            ],
+            "derives of `{name}`"
+        );
+    }
+}
+
+#[test]
+fn serde_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    override EXPOSURE: f32 = 1.0;
+    override SAMPLES: u32;
+
+    struct Material {
+        color: vec3<f32>,
+        transform: mat3x3<f32>,
+        roughness: f32,
+    }
+    struct Scene {
+        materials: array<Material>,
+    }
+    struct Palette {
+        colors: array<vec4<f32>, 64>,
+    }
+
+    @group(0) @binding(0) var<storage> scene: Scene;
+    @group(0) @binding(1) var<uniform> palette: Palette;
+
+    @compute
+    @workgroup_size(1)
+    fn main() {
+        let material = scene.materials[SAMPLES];
+        let color = palette.colors[0] * EXPOSURE;
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            gen_glam: true,
+            gen_serde: true,
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn doc_comments_snapshot() {
+    let source = r#"
+    /// The number of lights in the scene.
+    const LIGHT_COUNT: u32 = 4u;
+
+    /// How bright the scene is.
+    ///
+    /// Multiplies every light's intensity.
+    override EXPOSURE: f32 = 1.0;
+
+    /// A point light.
+    struct Light {
+        /// The position of the light, in world space.
+        @align(16) position: vec3<f32>,
+        // Not a doc comment.
+        intensity: f32,
+    }
+
+    /// All lights in the scene.
+    @group(0) @binding(0)
+    var<uniform> lights: array<Light, LIGHT_COUNT>;
+
+    /// Accumulates the light at each pixel.
+    @compute
+    @workgroup_size(1)
+    fn main() {
+        let intensity = lights[0].intensity * EXPOSURE;
+    }
+    "#;
+    let src = naga::front::wgsl::parse_str(source).unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            doc_comments: naga_to_tokenstream::docs::DocComments::from_wgsl(source),
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn doc_comments_skip_block_comments() {
+    let docs = naga_to_tokenstream::docs::DocComments::from_wgsl(
+        r#"
+    /* An unbalanced brace { in a comment,
+       /* which may be nested } } */
+       /// and a commented-out doc comment.
+       struct Hidden { value: u32, }
+    */
+    /// A point light.
+    struct Light {
+        /// The position of the light.
+        position: vec3<f32>, /* { */
+        /// The intensity of the light.
+        intensity: f32,
+    }
+
+    /// The number of lights, /* not a block comment.
+    const LIGHT_COUNT: u32 = 4u;
+    "#,
+    );
+
+    assert_eq!(docs.item("Hidden"), None);
+    assert_eq!(
+        docs.item("Light"),
+        Some(&[String::from("A point light.")][..])
+    );
+    assert_eq!(
+        docs.member("Light", "intensity"),
+        Some(&[String::from("The intensity of the light.")][..])
+    );
+    assert_eq!(
+        docs.item("LIGHT_COUNT"),
+        Some(
+            &[String::from(
+                "The number of lights, /* not a block comment."
+            )][..]
+        )
+    );
+}
+
+#[test]
+fn source_locations_snapshot() {
+    let source = r#"/// How bright the scene is.
+override EXPOSURE: f32 = 1.0;
+
+const SCALE: f32 = 2.0;
+
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> light: Light;
+
+@compute
+@workgroup_size(1)
+fn main() {
+    let intensity = light.intensity * EXPOSURE * SCALE;
+}
+"#;
+    let src = naga::front::wgsl::parse_str(source).unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            source: Some(naga_to_tokenstream::ModuleSource {
+                code: source.to_owned(),
+                path: Some("shaders/light.wgsl".into()),
+                embed_verbatim: true,
+            }),
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn doc_comments_only_read_from_wgsl_source() {
+    let source = "/// How bright the scene is.\noverride EXPOSURE: f32 = 1.0;\n";
+    let src = naga::front::wgsl::parse_str(source).unwrap();
+    let tokens = |path: &str| {
+        naga_to_tokenstream::ModuleToTokens::to_tokens(
+            &src,
+            naga_to_tokenstream::ModuleToTokensConfig {
+                source: Some(naga_to_tokenstream::ModuleSource {
+                    code: source.to_owned(),
+                    path: Some(path.into()),
+                    embed_verbatim: false,
+                }),
+                ..Default::default()
+            },
+        )
+        .to_string()
+    };
+
+    assert!(tokens("light.wgsl").contains("How bright the scene is."));
+    // Other languages' comments mean something else, so aren't read
+    assert!(!tokens("light.vert").contains("How bright the scene is."));
+}
+
+#[test]
+fn diagnostic_locations() {
+    let source = "const TOO_BIG: u32 = 5000000000;\n";
+
+    let mut module = naga::Module::default();
+    let u32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+        },
+        naga::Span::UNDEFINED,
+    );
+    let init = module.global_expressions.append(
+        naga::Expression::Literal(naga::Literal::AbstractInt(5_000_000_000)),
+        naga::Span::new(21, 31),
+    );
+    module.constants.append(
+        naga::Constant {
+            name: Some(String::from("TOO_BIG")),
+            ty: u32_ty,
+            init,
+        },
+        naga::Span::new(6, 13),
+    );
+
+    let cfg = naga_to_tokenstream::ModuleToTokensConfig {
+        source: Some(naga_to_tokenstream::ModuleSource {
+            code: source.to_owned(),
+            path: Some("constants.wgsl".into()),
+            embed_verbatim: false,
+        }),
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let mut types = naga_to_tokenstream::types::TypesDefinitions::new(&module, None, &cfg);
+    let mut diagnostics = naga_to_tokenstream::diagnostics::Diagnostics::new();
+    naga_to_tokenstream::constants::make_constants(&module, &mut types, &cfg, &mut diagnostics);
+
+    let messages: Vec<_> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        ["warning: the value `5000000000` of the constant `TOO_BIG` can't be represented as a `u32`, so no `VALUE` was generated\n --> constants.wgsl:1:7"]
+    );
+}
+
+#[test]
+fn array_size_constants() {
+    // `array<f32, 4>` has the same length as the unrelated `COUNT`, so must keep its literal length
+    let source = r#"
+    const COUNT: u32 = 4u;
+    const LIGHTS: u32 = 8u;
+
+    struct Scene {
+        weights: array<f32, 4>,
+        lights: array<vec4<f32>, LIGHTS>,
+        counts: array<u32, COUNT>,
+        // Commented out code doesn't count: array<f32, 8>
+        /* Nor does /* nested */ array<u32, 8> */
+    }
+    "#;
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let cfg = |source: Option<&str>| naga_to_tokenstream::ModuleToTokensConfig {
+        source: source.map(|code| naga_to_tokenstream::ModuleSource {
+            code: code.to_owned(),
+            path: None,
+            embed_verbatim: false,
+        }),
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let types = |cfg| {
+        let items =
+            naga_to_tokenstream::types::TypesDefinitions::new(&module, None, &cfg).definitions();
+        quote::quote!(#(#items)*).to_string()
+    };
+
+    let linked = types(cfg(Some(source)));
+    assert!(linked.contains("pub weights : [f32 ; 4u32 as usize]"));
+    assert!(linked.contains("pub lights : [[f32 ; 4] ; constants :: LIGHTS :: VALUE as usize]"));
+    // Naga only keeps the length, and another array of length 4 was written as a literal, so we can't tell
+    assert!(linked.contains("pub counts : [u32 ; 4u32 as usize]"));
+
+    // Without the source, lengths are always literals
+    let unlinked = types(cfg(None));
+    assert!(unlinked.contains("pub lights : [[f32 ; 4] ; 8u32 as usize]"));
+
+    // Falling back to a literal length which matches a constant is reported
+    let fallbacks = |cfg| {
+        let (_, diagnostics) =
+            naga_to_tokenstream::ModuleToTokens::to_items_with_diagnostics(&module, cfg);
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .filter(|message| message.starts_with("arrays of length"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        fallbacks(cfg(Some(source))),
+        ["arrays of length 4 are written with a literal length rather than the constant `COUNT`, as arrays of this length were written in more than one way, and naga merges them into one type"]
+    );
+    assert_eq!(
+        fallbacks(cfg(None)),
+        [
+            "arrays of length 4 are written with a literal length rather than the constant `COUNT`, as the WGSL source of the module wasn't given in `ModuleToTokensConfig::source`",
+            "arrays of length 8 are written with a literal length rather than the constant `LIGHTS`, as the WGSL source of the module wasn't given in `ModuleToTokensConfig::source`",
+        ]
+    );
+}
+
+#[test]
+fn source_diagnostics() {
+    // Runtime-sized arrays can't be uniform, which is only caught by validation
+    let source = "@group(0) @binding(0) var<uniform> values: array<f32>;\n";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let cfg = naga_to_tokenstream::ModuleToTokensConfig {
+        source: Some(naga_to_tokenstream::ModuleSource {
+            code: source.to_owned(),
+            path: Some("globals.wgsl".into()),
+            embed_verbatim: false,
+        }),
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let (_, diagnostics) =
+        naga_to_tokenstream::ModuleToTokens::to_items_with_diagnostics(&module, cfg);
+
+    let diagnostics: Vec<_> = diagnostics.into_iter().collect();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.starts_with(
+        "the module failed validation, so no sources were generated: Global variable [0] 'values' is invalid"
+    ));
+    // The validation error is rendered against the source
+    assert!(diagnostics[0].message.contains("globals.wgsl:1:23"));
+    assert!(diagnostics[0].message.contains(source.trim_end()));
+    assert_eq!(
+        diagnostics[0].location,
+        Some(naga_to_tokenstream::diagnostics::Location {
+            path: Some("globals.wgsl".into()),
+            line: 1,
+            column: 23,
+        })
+    );
+}
+
+#[test]
+fn validation_capabilities() {
+    let source = "var<private> value: f64;\n\n@compute @workgroup_size(1)\nfn main() {\n    value = 1.0lf;\n}\n";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let cfg = |capabilities| naga_to_tokenstream::ModuleToTokensConfig {
+        validation: naga_to_tokenstream::ValidationOptions {
+            flags: naga::valid::ValidationFlags::all(),
+            capabilities,
+        },
+        minify: naga_to_tokenstream::MinifyLevel::None,
+        ..Default::default()
+    };
+    let has_source = |items: &[syn::Item]| {
+        items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Const(constant) if constant.ident == "SOURCE"))
+    };
+
+    let (items, diagnostics) = naga_to_tokenstream::ModuleToTokens::to_items_with_diagnostics(
+        &module,
+        cfg(naga::valid::Capabilities::FLOAT64),
+    );
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert!(has_source(&items));
+
+    // Without the capability the module is invalid, so no sources can be generated
+    let (items, diagnostics) = naga_to_tokenstream::ModuleToTokens::to_items_with_diagnostics(
+        &module,
+        cfg(naga::valid::Capabilities::empty()),
+    );
+    assert!(diagnostics.has_errors());
+    let messages: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| &diagnostic.message)
+        .collect();
+    assert_eq!(messages.len(), 1, "{:?}", messages);
+    assert!(messages[0].starts_with("the module failed validation, so no sources were generated"));
+    assert!(!has_source(&items));
+}
+
+#[test]
+fn requirements_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    struct PushConstants {
+        scale: f32,
+    }
+
+    var<push_constant> push: PushConstants;
+    @group(0) @binding(0) var<storage, read_write> values: array<f32>;
+    @group(0) @binding(1) var output: texture_storage_2d<bgra8unorm, write>;
+
+    fn scale(value: f32) -> f32 {
+        return value * push.scale;
+    }
+
+    @compute
+    @workgroup_size(64)
+    fn scaled(@builtin(global_invocation_id) id: vec3<u32>) {
+        values[id.x] = scale(values[id.x]);
+    }
+
+    @compute
+    @workgroup_size(8, 8)
+    fn clear(@builtin(global_invocation_id) id: vec3<u32>) {
+        textureStore(output, id.xy, vec4(0.0));
+    }
+
+    @compute
+    @workgroup_size(64)
+    fn zero(@builtin(global_invocation_id) id: vec3<u32>) {
+        values[id.x] = 0.0;
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            gen_naga: true,
+            gen_wgpu: true,
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(prettyplease::unparse(&syn::parse2(tokens).unwrap()));
+}
+
+#[test]
+fn required_capabilities() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    @group(0) @binding(0) var<storage, read_write> minimum: atomic<u64>;
+    @group(0) @binding(1) var textures: binding_array<texture_2d<f32>, 4>;
+    @group(0) @binding(2) var linear: sampler;
+
+    @compute
+    @workgroup_size(1)
+    fn smallest() {
+        atomicMin(&minimum, 1lu);
+    }
+
+    @vertex
+    fn vertex(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+        return vec4(f32(subgroupAdd(index)));
+    }
+
+    @fragment
+    fn fragment(@location(0) @interpolate(flat) index: u32) -> @location(0) vec4<f32> {
+        return textureSample(textures[index], linear, vec2(0.0));
+    }
+    "#,
+    )
+    .unwrap();
+    let items = naga_to_tokenstream::ModuleToTokens::to_items(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            gen_naga: true,
+            ..Default::default()
+        },
+    );
+    let required_capabilities = |items: &[syn::Item]| {
+        items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Const(constant) if constant.ident == "REQUIRED_CAPABILITIES" => Some(
+                    quote::ToTokens::to_token_stream(&constant.expr)
+                        .to_string()
+                        .replace(' ', ""),
+                ),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let entry_point_items = |name: &str| {
+        let entry_points = items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Mod(module) if module.ident == "entry_points" => {
+                    Some(module.content.as_ref().unwrap().1.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+        entry_points
+            .into_iter()
+            .find_map(|item| match item {
+                syn::Item::Mod(module) if module.ident == name => Some(module.content.unwrap().1),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    // 64-bit atomic minimums in storage only need the lesser 64-bit atomic capability
+    assert_eq!(
+        required_capabilities(&entry_point_items("smallest")),
+        "naga::valid::Capabilities::SHADER_INT64.union(naga::valid::Capabilities::SHADER_INT64_ATOMIC_MIN_MAX)"
+    );
+    assert_eq!(
+        required_capabilities(&entry_point_items("vertex")),
+        "naga::valid::Capabilities::SUBGROUP.union(naga::valid::Capabilities::SUBGROUP_VERTEX_STAGE)"
+    );
+    assert_eq!(
+        required_capabilities(&entry_point_items("fragment")),
+        "naga::valid::Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING"
+    );
+    assert_eq!(
+        required_capabilities(&items),
+        "naga::valid::Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING.union(naga::valid::Capabilities::SHADER_INT64).union(naga::valid::Capabilities::SUBGROUP).union(naga::valid::Capabilities::SUBGROUP_VERTEX_STAGE).union(naga::valid::Capabilities::SHADER_INT64_ATOMIC_MIN_MAX)"
+    );
+}
+
+#[test]
+fn exclusive_source_snapshot() {
+    let src = naga::front::wgsl::parse_str(
+        r#"
+    struct Particle {
+        position: vec2<f32>,
+        velocity: vec2<f32>,
+    }
+
+    struct Unused {
+        value: u32,
+    }
+
+    const DAMPING: f32 = 0.5;
+    const UNUSED: Unused = Unused(1u);
+    const TIME_STEP: f32 = 0.01;
+    const CLEAR_VALUE: f32 = 0.0;
+
+    @group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+    @group(0) @binding(1) var<storage, read_write> values: array<f32>;
+
+    fn damp(velocity: vec2<f32>) -> vec2<f32> {
+        return velocity * DAMPING;
+    }
+
+    @compute
+    @workgroup_size(64)
+    fn step(@builtin(global_invocation_id) id: vec3<u32>) {
+        var particle = particles[id.x];
+        particle.position += particle.velocity * TIME_STEP;
+        particle.velocity = damp(particle.velocity);
+        particles[id.x] = particle;
+    }
+
+    @compute
+    @workgroup_size(64)
+    fn clear(@builtin(global_invocation_id) id: vec3<u32>) {
+        values[id.x] = CLEAR_VALUE;
+    }
+    "#,
+    )
+    .unwrap();
+    let tokens = naga_to_tokenstream::ModuleToTokens::to_tokens(
+        &src,
+        naga_to_tokenstream::ModuleToTokensConfig {
+            minify: naga_to_tokenstream::MinifyLevel::None,
+            ..Default::default()
         },
     );
 